@@ -19,6 +19,10 @@ impl Matrix {
         }
     }
 
+    pub fn identity() -> Matrix {
+        Matrix::scale(1.0, 1.0, 1.0)
+    }
+
     pub fn translate(x: f64, y: f64, z: f64) -> Matrix {
         Matrix {
             size: 4,
@@ -103,12 +107,20 @@ impl Matrix {
         }
     }
 
+    pub fn multiply_with_direction(&self, direction: &Vert) -> Vert {
+        Vert {
+            x: self.data[0] * direction.x + self.data[1] * direction.y + self.data[2] * direction.z,
+            y: self.data[4] * direction.x + self.data[5] * direction.y + self.data[6] * direction.z,
+            z: self.data[8] * direction.x + self.data[9] * direction.y + self.data[10] * direction.z,
+        }
+    }
+
     pub fn transpose(self) -> Matrix {
         let mut transposed = Matrix::new(4);
         let mut src_index = 0;
-        for row in 0..self.size as usize {
-            for column in 0..self.size as usize {
-                transposed.data[src_index] = self.data[column * self.size as usize + row];
+        for row in 0..self.size {
+            for column in 0..self.size {
+                transposed.data[src_index] = self.data[column * self.size + row];
                 src_index += 1;
             }
         }
@@ -174,7 +186,7 @@ impl Matrix {
         };
         let det = self.determinant();
         if det == 0.0 {
-            for i in 0..self.size.pow(2) as usize {
+            for i in 0..self.size.pow(2) {
                 answer.data[i] = self.data[i];
             }
             answer
@@ -198,7 +210,7 @@ impl core::ops::Mul for Matrix {
         for row_start in (0..16).step_by(4) {
             for column in 0..4 {
                 let answer_index = row_start + column;
-                answer.data[answer_index] += self.data[row_start + 0] * other.data[0 + column];
+                answer.data[answer_index] += self.data[row_start] * other.data[column];
                 answer.data[answer_index] += self.data[row_start + 1] * other.data[4 + column];
                 answer.data[answer_index] += self.data[row_start + 2] * other.data[8 + column];
                 answer.data[answer_index] += self.data[row_start + 3] * other.data[12 + column];
@@ -1,4 +1,6 @@
 mod matrix;
+mod transform;
 mod vert;
 pub use matrix::Matrix;
+pub use transform::Transform;
 pub use vert::Vert;
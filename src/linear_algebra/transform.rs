@@ -0,0 +1,61 @@
+use crate::{BoundingBox, Matrix, Ray, Vert};
+
+#[derive(Clone)]
+pub struct Transform {
+    matrix: Matrix,
+    inverse: Matrix,
+    normal_matrix: Matrix,
+}
+
+impl Transform {
+    pub fn new(matrix: Matrix) -> Transform {
+        let inverse = matrix.inverse();
+        let normal_matrix = inverse.clone().transpose();
+        Transform {
+            matrix,
+            inverse,
+            normal_matrix,
+        }
+    }
+
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    pub fn ray_to_object(&self, ray: &Ray) -> Ray {
+        ray.transform(&self.inverse)
+    }
+
+    pub fn point_to_object(&self, point: &Vert) -> Vert {
+        self.inverse.multiply_with_vert(point)
+    }
+
+    pub fn object_scale(&self) -> f64 {
+        self.inverse.determinant().abs().cbrt()
+    }
+
+    pub fn normal_to_world(&self, normal: &Vert) -> Vert {
+        self.normal_matrix.multiply_with_direction(normal).normalise()
+    }
+
+    pub fn bounds_to_world(&self, bounds: &BoundingBox) -> BoundingBox {
+        let mut world_bounds = BoundingBox::empty();
+        if bounds.is_empty() {
+            return world_bounds;
+        }
+        for &x in &[bounds.min.x, bounds.max.x] {
+            for &y in &[bounds.min.y, bounds.max.y] {
+                for &z in &[bounds.min.z, bounds.max.z] {
+                    world_bounds.add_point(&self.matrix.multiply_with_vert(&Vert::new(x, y, z)));
+                }
+            }
+        }
+        world_bounds
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::new(Matrix::identity())
+    }
+}
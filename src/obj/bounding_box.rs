@@ -0,0 +1,90 @@
+use crate::{Ray, Vert, MACHEPS};
+
+#[derive(Clone, Debug)]
+pub struct BoundingBox {
+    pub min: Vert,
+    pub max: Vert,
+}
+
+impl BoundingBox {
+    pub fn empty() -> BoundingBox {
+        BoundingBox {
+            min: Vert::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: Vert::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    pub fn from_points(points: &[&Vert]) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        for point in points {
+            bounds.add_point(point);
+        }
+        bounds
+    }
+
+    pub fn add_point(&mut self, point: &Vert) {
+        self.min = Vert::new(self.min.x.min(point.x), self.min.y.min(point.y), self.min.z.min(point.z));
+        self.max = Vert::new(self.max.x.max(point.x), self.max.y.max(point.y), self.max.z.max(point.z));
+    }
+
+    pub fn add_box(&mut self, other: &BoundingBox) {
+        if other.is_empty() {
+            return;
+        }
+        self.add_point(&other.min);
+        self.add_point(&other.max);
+    }
+
+    pub fn overlap(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: Vert::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y), self.min.z.max(other.min.z)),
+            max: Vert::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y), self.max.z.min(other.max.z)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.min.x.is_finite() && self.min.y.is_finite() && self.min.z.is_finite()
+            && self.max.x.is_finite() && self.max.y.is_finite() && self.max.z.is_finite()
+    }
+
+    pub fn centroid(&self) -> Vert {
+        (self.min.clone() + self.max.clone()).multiply_by_scalar(0.5)
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let extent = self.max.clone() - self.min.clone();
+        2.0 * (extent.x * extent.y + extent.y * extent.z + extent.z * extent.x)
+    }
+
+    pub fn intersects_with(&self, ray: &Ray, inv_direction: &Vert, max_time: f64) -> Option<f64> {
+        let (x_min, x_max) = slab(ray.origin.x, inv_direction.x, self.min.x, self.max.x);
+        let (y_min, y_max) = slab(ray.origin.y, inv_direction.y, self.min.y, self.max.y);
+        let (z_min, z_max) = slab(ray.origin.z, inv_direction.z, self.min.z, self.max.z);
+        let t_min = x_min.max(y_min).max(z_min).max(0.0);
+        let t_max = x_max.min(y_max).min(z_max).min(max_time);
+        if t_min <= t_max {
+            Some(t_min)
+        } else {
+            None
+        }
+    }
+}
+
+fn slab(origin: f64, inv_direction: f64, min: f64, max: f64) -> (f64, f64) {
+    let t1 = (min - MACHEPS - origin) * inv_direction;
+    let t2 = (max + MACHEPS - origin) * inv_direction;
+    if t1.is_nan() || t2.is_nan() {
+        (f64::NEG_INFINITY, f64::INFINITY)
+    } else if t1 < t2 {
+        (t1, t2)
+    } else {
+        (t2, t1)
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{BoundingBox, Ray, Vert};
+
+const BINS: usize = 12;
+const MAX_LEAF_SIZE: usize = 4;
+const MAX_DEPTH: usize = 48;
+const TRAVERSAL_COST: f64 = 0.125;
+
+#[derive(Clone, Debug)]
+pub struct BvhNode {
+    pub bounds: BoundingBox,
+    pub start: usize,
+    pub count: usize,
+}
+
+impl BvhNode {
+    pub fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Bvh {
+    pub nodes: Vec<BvhNode>,
+    pub indices: Vec<usize>,
+}
+
+#[derive(Clone)]
+struct Bin {
+    bounds: BoundingBox,
+    count: usize,
+}
+
+fn axis_of(vert: &Vert, axis: usize) -> f64 {
+    match axis {
+        0 => vert.x,
+        1 => vert.y,
+        _ => vert.z,
+    }
+}
+
+impl Bvh {
+    pub fn new(bounds: &[BoundingBox]) -> Bvh {
+        let mut bvh = Bvh {
+            nodes: vec![],
+            indices: (0..bounds.len()).collect(),
+        };
+        if !bounds.is_empty() {
+            let centroids: Vec<Vert> = bounds.iter().map(|bounds| bounds.centroid()).collect();
+            bvh.build(bounds, &centroids, 0, bounds.len(), 0);
+        }
+        bvh
+    }
+
+    pub fn bounds(&self) -> BoundingBox {
+        match self.nodes.first() {
+            Some(root) => root.bounds.clone(),
+            None => BoundingBox::empty(),
+        }
+    }
+
+    pub fn refit(&mut self, bounds: &[BoundingBox]) {
+        for node_index in (0..self.nodes.len()).rev() {
+            let mut node_bounds = BoundingBox::empty();
+            let node = &self.nodes[node_index];
+            if node.is_leaf() {
+                for &index in &self.indices[node.start..node.start + node.count] {
+                    node_bounds.add_box(&bounds[index]);
+                }
+            } else {
+                node_bounds.add_box(&self.nodes[node_index + 1].bounds);
+                node_bounds.add_box(&self.nodes[node.start].bounds);
+            }
+            self.nodes[node_index].bounds = node_bounds;
+        }
+    }
+
+    fn build(&mut self, bounds: &[BoundingBox], centroids: &[Vert], start: usize, end: usize, depth: usize) -> usize {
+        let node_index = self.nodes.len();
+        let mut node_bounds = BoundingBox::empty();
+        let mut centroid_bounds = BoundingBox::empty();
+        for &index in &self.indices[start..end] {
+            node_bounds.add_box(&bounds[index]);
+            centroid_bounds.add_point(&centroids[index]);
+        }
+        self.nodes.push(BvhNode {
+            bounds: node_bounds,
+            start,
+            count: end - start,
+        });
+        if end - start == 1 || depth == MAX_DEPTH {
+            return node_index;
+        }
+        if let Some(mid) = self.split(bounds, centroids, &centroid_bounds, start, end) {
+            self.build(bounds, centroids, start, mid, depth + 1);
+            let right = self.build(bounds, centroids, mid, end, depth + 1);
+            let node = &mut self.nodes[node_index];
+            node.start = right;
+            node.count = 0;
+        }
+        node_index
+    }
+
+    fn split(&mut self, bounds: &[BoundingBox], centroids: &[Vert], centroid_bounds: &BoundingBox, start: usize, end: usize) -> Option<usize> {
+        let count = end - start;
+        let parent_area = self.nodes[self.nodes.len() - 1].bounds.surface_area();
+        let mut best: Option<(f64, usize, usize)> = None;
+        for axis in 0..3 {
+            let min = axis_of(&centroid_bounds.min, axis);
+            let extent = axis_of(&centroid_bounds.max, axis) - min;
+            if extent <= 0.0 {
+                continue;
+            }
+            let bin_of = |index: usize| (((axis_of(&centroids[index], axis) - min) / extent * BINS as f64) as usize).min(BINS - 1);
+            let mut bins = vec![Bin {bounds: BoundingBox::empty(), count: 0}; BINS];
+            for &index in &self.indices[start..end] {
+                let bin = &mut bins[bin_of(index)];
+                bin.bounds.add_box(&bounds[index]);
+                bin.count += 1;
+            }
+            for split in 1..BINS {
+                let mut left = BoundingBox::empty();
+                let mut right = BoundingBox::empty();
+                let mut left_count = 0;
+                let mut right_count = 0;
+                for bin in &bins[..split] {
+                    left.add_box(&bin.bounds);
+                    left_count += bin.count;
+                }
+                for bin in &bins[split..] {
+                    right.add_box(&bin.bounds);
+                    right_count += bin.count;
+                }
+                if left_count == 0 || right_count == 0 {
+                    continue;
+                }
+                let cost = TRAVERSAL_COST + (left.surface_area() * left_count as f64 + right.surface_area() * right_count as f64) / parent_area;
+                if best.is_none_or(|(best_cost, ..)| cost < best_cost) {
+                    best = Some((cost, axis, split));
+                }
+            }
+        }
+        let (cost, axis, split) = best?;
+        if count <= MAX_LEAF_SIZE && cost >= count as f64 {
+            return None;
+        }
+        let min = axis_of(&centroid_bounds.min, axis);
+        let extent = axis_of(&centroid_bounds.max, axis) - min;
+        let bin_of = |index: usize| (((axis_of(&centroids[index], axis) - min) / extent * BINS as f64) as usize).min(BINS - 1);
+        let mut mid = start;
+        for index in start..end {
+            if bin_of(self.indices[index]) < split {
+                self.indices.swap(index, mid);
+                mid += 1;
+            }
+        }
+        Some(mid)
+    }
+
+    pub fn traverse<F: FnMut(usize, &mut f64) -> bool>(&self, ray: &Ray, mut max_time: f64, mut visit: F) {
+        let inv_direction = Vert::new(1.0 / ray.direction.x, 1.0 / ray.direction.y, 1.0 / ray.direction.z);
+        let root_time = match self.nodes.first() {
+            Some(root) => root.bounds.intersects_with(ray, &inv_direction, max_time),
+            None => None,
+        };
+        let mut stack = [(0, 0.0); MAX_DEPTH + 2];
+        let mut len = 0;
+        if let Some(time) = root_time {
+            stack[0] = (0, time);
+            len = 1;
+        }
+        while len > 0 {
+            len -= 1;
+            let (node_index, entry_time) = stack[len];
+            if entry_time > max_time {
+                continue;
+            }
+            let node = &self.nodes[node_index];
+            if node.is_leaf() {
+                for &index in &self.indices[node.start..node.start + node.count] {
+                    if visit(index, &mut max_time) {
+                        return;
+                    }
+                }
+                continue;
+            }
+            let left = node_index + 1;
+            let right = node.start;
+            let left_time = self.nodes[left].bounds.intersects_with(ray, &inv_direction, max_time);
+            let right_time = self.nodes[right].bounds.intersects_with(ray, &inv_direction, max_time);
+            match (left_time, right_time) {
+                (Some(left_time), Some(right_time)) => {
+                    if left_time <= right_time {
+                        stack[len] = (right, right_time);
+                        stack[len + 1] = (left, left_time);
+                    } else {
+                        stack[len] = (left, left_time);
+                        stack[len + 1] = (right, right_time);
+                    }
+                    len += 2;
+                },
+                (Some(left_time), None) => {
+                    stack[len] = (left, left_time);
+                    len += 1;
+                },
+                (None, Some(right_time)) => {
+                    stack[len] = (right, right_time);
+                    len += 1;
+                },
+                (None, None) => {},
+            }
+        }
+    }
+
+    pub fn closest_hit<T, F: FnMut(usize, f64) -> Option<(f64, T)>>(&self, ray: &Ray, max_time: f64, mut visit: F) -> Option<T> {
+        let mut closest = None;
+        self.traverse(ray, max_time, |index, max_time| {
+            if let Some((time, hit)) = visit(index, *max_time) {
+                if time < *max_time {
+                    *max_time = time;
+                    closest = Some(hit);
+                }
+            }
+            false
+        });
+        closest
+    }
+
+    pub fn any_hit<F: FnMut(usize, f64) -> bool>(&self, ray: &Ray, max_time: f64, mut visit: F) -> bool {
+        let mut hit = false;
+        self.traverse(ray, max_time, |index, max_time| {
+            hit = visit(index, *max_time);
+            hit
+        });
+        hit
+    }
+}
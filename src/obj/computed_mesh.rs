@@ -1,32 +1,112 @@
-use crate::{ComputedTriangle, Intersection, Mesh, Ray};
-use std::sync::Arc;
+use crate::{BoundingBox, Bvh, ComputedTriangle, Intersection, Ray, Shape};
 
 pub struct ComputedMesh {
     pub triangles: Vec<ComputedTriangle>,
+    pub bvh: Bvh,
 }
 
 impl ComputedMesh {
+    pub fn new(triangles: Vec<ComputedTriangle>) -> ComputedMesh {
+        let bounds: Vec<BoundingBox> = triangles.iter().map(|triangle| triangle.bounds()).collect();
+        let bvh = Bvh::new(&bounds);
+        ComputedMesh {triangles, bvh}
+    }
+
+    pub fn refit(&self, triangles: Vec<ComputedTriangle>) -> ComputedMesh {
+        let bounds: Vec<BoundingBox> = triangles.iter().map(|triangle| triangle.bounds()).collect();
+        let mut bvh = self.bvh.clone();
+        bvh.refit(&bounds);
+        ComputedMesh {triangles, bvh}
+    }
+
     pub fn intersects_with<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
-        for triangle in &self.triangles {
-            if let Some((time, u, v)) = triangle.intersects_with(&ray) {
-                let intersection = Intersection {
-                    time,
-                    u,
-                    v,
-                    triangle,
-                };
-                intersections.push(intersection);
+        self.bvh.traverse(ray, f64::INFINITY, |index, _| {
+            let triangle = &self.triangles[index];
+            if let Some((time, u, v)) = triangle.intersects_with(ray) {
+                intersections.push(Intersection::new(time, u, v, triangle));
             }
-        }
+            false
+        });
     }
 }
 
-pub trait ComputeMeshes {
-    fn compute_meshes(&self) -> Vec<Arc<ComputedMesh>>;
+impl Shape for ComputedMesh {
+    fn bounds(&self) -> BoundingBox {
+        self.bvh.bounds()
+    }
+
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        self.bvh.closest_hit(ray, max_time, |index, _| {
+            let triangle = &self.triangles[index];
+            triangle.intersects_with(ray).map(|(time, u, v)| (time, Intersection::new(time, u, v, triangle)))
+        })
+    }
+
+    fn any_hit(&self, ray: &Ray, max_time: f64) -> bool {
+        self.bvh.any_hit(ray, max_time, |index, max_time| {
+            match self.triangles[index].intersects_with(ray) {
+                Some((time, ..)) => time < max_time,
+                None => false,
+            }
+        })
+    }
+
+    fn all_hits<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
+        self.intersects_with(ray, intersections);
+        let mut behind = vec![];
+        self.intersects_with(&Ray::new(ray.origin.clone(), -ray.direction.clone()), &mut behind);
+        intersections.extend(behind.into_iter().map(|mut intersection| {
+            intersection.time = -intersection.time;
+            intersection
+        }));
+    }
 }
 
-impl ComputeMeshes for Vec<Arc<Mesh>> {
-    fn compute_meshes(&self) -> Vec<Arc<ComputedMesh>> {
-        self.iter().map(|mesh| Arc::new(mesh.compute())).collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Mesh, Vert};
+
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> f64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (self.0 >> 11) as f64 / (1u64 << 53) as f64
+        }
+
+        fn point_in(&mut self, bounds: &BoundingBox) -> Vert {
+            let size = bounds.max.clone() - bounds.min.clone();
+            Vert::new(
+                bounds.min.x + size.x * (self.next() * 1.5 - 0.25),
+                bounds.min.y + size.y * (self.next() * 1.5 - 0.25),
+                bounds.min.z + size.z * (self.next() * 1.5 - 0.25),
+            )
+        }
+    }
+
+    fn linear_closest_hit(mesh: &ComputedMesh, ray: &Ray) -> Option<f64> {
+        mesh.triangles.iter()
+            .filter_map(|triangle| triangle.intersects_with(ray).map(|(time, ..)| time))
+            .fold(None, |closest: Option<f64>, time| Some(closest.map_or(time, |closest| closest.min(time))))
+    }
+
+    #[test]
+    fn bvh_queries_match_linear_scan() {
+        let mesh = Mesh::load_obj("objs/gitaroo/u1.obj").expect("mesh").compute();
+        let bounds = mesh.bounds();
+        let mut random = Lcg(1);
+        let mut hits = 0;
+        for _ in 0..1000 {
+            let origin = random.point_in(&bounds);
+            let direction = (random.point_in(&bounds) - origin.clone()).normalise();
+            let ray = Ray::new(origin, direction);
+            let expected = linear_closest_hit(&mesh, &ray);
+            hits += expected.is_some() as usize;
+            assert_eq!(mesh.closest_hit(&ray, f64::INFINITY).map(|intersection| intersection.time), expected);
+            let max_time = random.next() * 2.0 * expected.unwrap_or(1.0);
+            assert_eq!(mesh.any_hit(&ray, max_time), expected.is_some_and(|time| time < max_time));
+        }
+        assert!(hits > 100, "only {} of the rays hit the mesh", hits);
     }
 }
@@ -1,8 +1,11 @@
-use crate::{Material, Ray, Triangle, Vert, MACHEPS, UVMap};
+use crate::{BoundingBox, Intersection, Material, Ray, Shape, Surface, Triangle, Vert, MACHEPS, UVMap};
 
 pub struct ComputedTriangle {
     p1: Vert,
     pub normals: Option<(Vert, Vert, Vert)>,
+    pub tangents: Option<(Vert, Vert, Vert)>,
+    handedness: f64,
+    uv_density: f64,
     e1: Vert,
     e2: Vert,
     pub norm_v: Vert,
@@ -11,13 +14,26 @@ pub struct ComputedTriangle {
 }
 
 impl ComputedTriangle {
-    pub fn new(p1: Vert, p2: Vert, p3: Vert, normals: Option<(Vert, Vert, Vert)>, material: Material, uv_map: Option<UVMap>) -> ComputedTriangle {
+    pub fn new(p1: Vert, p2: Vert, p3: Vert, normals: Option<(Vert, Vert, Vert)>, tangents: Option<(Vert, Vert, Vert)>, material: Material, uv_map: Option<UVMap>) -> ComputedTriangle {
         let e1 = p2.clone() - p1.clone();
         let e2 = p3.clone() - p1.clone();
         let norm_v = e2.cross_product(&e1).normalise();
+        let uv_tangent = uv_map.as_ref().and_then(|uv_map| uv_map.tangent(&e1, &e2));
+        let handedness = uv_tangent.as_ref().map_or(1.0, |(_, handedness)| *handedness);
+        let uv_density = uv_map.as_ref().map_or(0.0, |uv_map| {
+            let d1 = uv_map.t2.clone() - uv_map.t1.clone();
+            let d2 = uv_map.t3.clone() - uv_map.t1.clone();
+            let uv_area = (d1.x * d2.y - d2.x * d1.y).abs();
+            let area = e1.cross_product(&e2).magnitude();
+            if area > 0.0 { (uv_area / area).sqrt() } else { 0.0 }
+        });
+        let tangents = tangents.or_else(|| uv_tangent.map(|(tangent, _)| (tangent.clone(), tangent.clone(), tangent)));
         ComputedTriangle {
             p1,
             normals,
+            tangents,
+            handedness,
+            uv_density,
             e1,
             e2,
             norm_v,
@@ -35,7 +51,7 @@ impl ComputedTriangle {
         let f = 1.0 / det;
         let p1_to_origin = ray.origin.clone() - self.p1.clone();
         let u = f * p1_to_origin.dot_product(&dir_cross_e2);
-        if u < 0.0 || u > 1.0 {
+        if !(0.0..=1.0).contains(&u) {
             return None;
         }
         let origin_cross_e1 = p1_to_origin.cross_product(&self.e1);
@@ -47,10 +63,44 @@ impl ComputedTriangle {
         if time < 0.0 {
             return None;
         }
+        if let Some(uv_map) = &self.uv_map {
+            let (map_u, map_v) = uv_map.uv_at(u, v);
+            if self.material.cut_out_at(map_u, map_v) {
+                return None;
+            }
+        }
         Some((time, u, v))
     }
 
     pub fn norm_vec_at_uv(&self, u: f64, v: f64) -> Vert {
+        let norm_v = self.interpolated_norm_vec(u, v);
+        let (uv_map, (t1, t2, t3)) = match (&self.uv_map, &self.tangents) {
+            (Some(uv_map), Some(tangents)) if self.material.normal_map.is_some() || self.material.bump_map.is_some() => (uv_map, tangents),
+            _ => return norm_v,
+        };
+        let (map_u, map_v) = uv_map.uv_at(u, v);
+        let tangent = t2.multiply_by_scalar(u) + t3.multiply_by_scalar(v) + t1.multiply_by_scalar(1.0 - u - v);
+        let outward = -norm_v.normalise();
+        let tangent = (tangent.clone() - outward.multiply_by_scalar(outward.dot_product(&tangent))).normalise();
+        let bitangent = outward.cross_product(&tangent).multiply_by_scalar(self.handedness);
+        let perturbed = match (&self.material.normal_map, &self.material.bump_map) {
+            (Some(normal_map), _) => {
+                let sample = normal_map.colour_at_uv(map_u, map_v);
+                let x = (sample.x * 2.0 - 1.0) * normal_map.bump_multiplier;
+                let y = (sample.y * 2.0 - 1.0) * normal_map.bump_multiplier;
+                let z = sample.z * 2.0 - 1.0;
+                tangent.multiply_by_scalar(x) + bitangent.multiply_by_scalar(y) + outward.multiply_by_scalar(z)
+            },
+            (None, Some(bump_map)) => {
+                let (du, dv) = bump_map.gradient_at_uv(map_u, map_v);
+                outward - tangent.multiply_by_scalar(du) - bitangent.multiply_by_scalar(dv)
+            },
+            (None, None) => outward,
+        };
+        -perturbed.normalise()
+    }
+
+    fn interpolated_norm_vec(&self, u: f64, v: f64) -> Vert {
         match &self.normals {
             Some((n1, n2, n3)) => {
                 n2.multiply_by_scalar(u) +
@@ -60,15 +110,41 @@ impl ComputedTriangle {
             None => self.norm_v.clone(),
         }
     }
+}
+
+impl Shape for ComputedTriangle {
+    fn bounds(&self) -> BoundingBox {
+        let p2 = self.p1.clone() + self.e1.clone();
+        let p3 = self.p1.clone() + self.e2.clone();
+        BoundingBox::from_points(&[&self.p1, &p2, &p3])
+    }
 
-    pub fn colour_at_uv(&self, u: f64, v: f64) -> Vert {
-        match &self.uv_map {
-            Some(uv_map) => uv_map.colour_at(u, v),
-            None => self.material.colour.clone(),
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        match self.intersects_with(ray) {
+            Some((time, u, v)) if time < max_time => Some(Intersection::new(time, u, v, self)),
+            _ => None,
         }
     }
 }
 
+impl Surface for ComputedTriangle {
+    fn norm_vec_at(&self, _point: &Vert, u: f64, v: f64) -> Vert {
+        self.norm_vec_at_uv(u, v)
+    }
+
+    fn uv_at(&self, _point: &Vert, u: f64, v: f64) -> Option<(f64, f64)> {
+        self.uv_map.as_ref().map(|uv_map| uv_map.uv_at(u, v))
+    }
+
+    fn uv_density(&self) -> f64 {
+        self.uv_density
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+}
+
 pub trait ComputeTriangles {
     fn compute_triangles(&self) -> Vec<ComputedTriangle>;
 }
@@ -0,0 +1,140 @@
+use crate::{BoundingBox, Intersection, Material, Matrix, Ray, Shape, Surface, Transform, Vert, MACHEPS, PI};
+
+#[derive(Clone)]
+pub struct Cone {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub transform: Transform,
+    pub material: Material,
+}
+
+impl Cone {
+    pub fn new(minimum: f64, maximum: f64, transform: Matrix) -> Cone {
+        Cone {
+            minimum,
+            maximum,
+            transform: Transform::new(transform),
+            material: Material::default(),
+        }
+    }
+
+    pub fn intersects_with<F: FnMut(f64)>(&self, ray: &Ray, mut visit: F) {
+        let (origin, direction) = (&ray.origin, &ray.direction);
+        let a = direction.x.powi(2) - direction.y.powi(2) + direction.z.powi(2);
+        let b = 2.0 * (origin.x * direction.x - origin.y * direction.y + origin.z * direction.z);
+        let c = origin.x.powi(2) - origin.y.powi(2) + origin.z.powi(2);
+        let mut visit_side = |time: f64| {
+            let y = origin.y + time * direction.y;
+            if self.minimum < y && y < self.maximum {
+                visit(time);
+            }
+        };
+        if a.abs() < MACHEPS {
+            if b.abs() >= MACHEPS {
+                visit_side(-c / (2.0 * b));
+            }
+        } else {
+            let discriminant = b.powi(2) - 4.0 * a * c;
+            if discriminant >= 0.0 {
+                let root = discriminant.sqrt();
+                visit_side((-b - root) / (2.0 * a));
+                visit_side((-b + root) / (2.0 * a));
+            }
+        }
+        if direction.y.abs() >= MACHEPS {
+            for &y in &[self.minimum, self.maximum] {
+                let time = (y - origin.y) / direction.y;
+                let x = origin.x + time * direction.x;
+                let z = origin.z + time * direction.z;
+                if x.powi(2) + z.powi(2) <= y.powi(2) {
+                    visit(time);
+                }
+            }
+        }
+    }
+
+    fn on_cap(&self, point: &Vert) -> Option<f64> {
+        let distance = point.x.powi(2) + point.z.powi(2);
+        if distance < self.maximum.powi(2) && point.y >= self.maximum - MACHEPS {
+            Some(1.0)
+        } else if distance < self.minimum.powi(2) && point.y <= self.minimum + MACHEPS {
+            Some(-1.0)
+        } else {
+            None
+        }
+    }
+}
+
+impl Shape for Cone {
+    fn bounds(&self) -> BoundingBox {
+        let limit = self.minimum.abs().max(self.maximum.abs());
+        let bounds = BoundingBox {
+            min: Vert::new(-limit, self.minimum, -limit),
+            max: Vert::new(limit, self.maximum, limit),
+        };
+        self.transform.bounds_to_world(&bounds)
+    }
+
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        let mut closest: Option<f64> = None;
+        self.intersects_with(&self.transform.ray_to_object(ray), |time| {
+            if time >= 0.0 && time < closest.unwrap_or(max_time) {
+                closest = Some(time);
+            }
+        });
+        closest.map(|time| Intersection::new(time, 0.0, 0.0, self))
+    }
+
+    fn all_hits<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
+        self.intersects_with(&self.transform.ray_to_object(ray), |time| {
+            intersections.push(Intersection::new(time, 0.0, 0.0, self));
+        });
+    }
+}
+
+impl Surface for Cone {
+    fn norm_vec_at(&self, point: &Vert, _u: f64, _v: f64) -> Vert {
+        let point = self.transform.point_to_object(point);
+        let normal = match self.on_cap(&point) {
+            Some(direction) => Vert::new(0.0, direction, 0.0),
+            None => {
+                let y = (point.x.powi(2) + point.z.powi(2)).sqrt();
+                let y = if point.y > 0.0 { -y } else { y };
+                Vert::new(point.x, y, point.z)
+            },
+        };
+        self.transform.normal_to_world(&-normal)
+    }
+
+    fn uv_at(&self, point: &Vert, _u: f64, _v: f64) -> Option<(f64, f64)> {
+        let point = self.transform.point_to_object(point);
+        match self.on_cap(&point) {
+            Some(direction) => {
+                let radius = if direction > 0.0 { self.maximum.abs() } else { self.minimum.abs() };
+                Some(((point.x / radius + 1.0) / 2.0, (point.z / radius + 1.0) / 2.0))
+            },
+            None => {
+                let u = point.x.atan2(point.z) / (2.0 * PI) + 0.5;
+                let v = (point.y - self.minimum) / (self.maximum - self.minimum);
+                Some((u, v))
+            },
+        }
+    }
+
+    fn object_point(&self, point: &Vert) -> Vert {
+        self.transform.point_to_object(point)
+    }
+
+    fn uv_density(&self) -> f64 {
+        let height = self.maximum - self.minimum;
+        let radius = self.minimum.abs().max(self.maximum.abs());
+        if !height.is_finite() || radius <= 0.0 {
+            return 0.0;
+        }
+        (1.0 / height).max(0.5 / radius) * self.transform.object_scale()
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+}
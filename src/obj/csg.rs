@@ -0,0 +1,88 @@
+use crate::{BoundingBox, Intersection, Ray, Shape, SortByTime};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    fn allowed(&self, left_hit: bool, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => (left_hit && !in_right) || (!left_hit && !in_left),
+            CsgOperation::Intersection => (left_hit && in_right) || (!left_hit && in_left),
+            CsgOperation::Difference => (left_hit && !in_right) || (!left_hit && in_left),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Csg {
+    pub operation: CsgOperation,
+    pub left: Arc<dyn Shape>,
+    pub right: Arc<dyn Shape>,
+}
+
+impl Csg {
+    pub fn new<L: Shape + 'static, R: Shape + 'static>(operation: CsgOperation, left: L, right: R) -> Csg {
+        Csg {
+            operation,
+            left: Arc::new(left),
+            right: Arc::new(right),
+        }
+    }
+}
+
+impl Shape for Csg {
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = self.left.bounds();
+        match self.operation {
+            CsgOperation::Union => bounds.add_box(&self.right.bounds()),
+            CsgOperation::Intersection => bounds = bounds.overlap(&self.right.bounds()),
+            CsgOperation::Difference => {},
+        }
+        bounds
+    }
+
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        let mut intersections = vec![];
+        self.all_hits(ray, &mut intersections);
+        intersections.into_iter().find(|intersection| intersection.time >= 0.0 && intersection.time < max_time)
+    }
+
+    fn all_hits<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
+        let mut left = vec![];
+        let mut right = vec![];
+        self.left.all_hits(ray, &mut left);
+        self.right.all_hits(ray, &mut right);
+        left.sort_by_time();
+        right.sort_by_time();
+        let mut left = left.into_iter().peekable();
+        let mut right = right.into_iter().peekable();
+        let mut in_left = false;
+        let mut in_right = false;
+        loop {
+            let left_hit = match (left.peek(), right.peek()) {
+                (Some(left_next), Some(right_next)) => left_next.time <= right_next.time,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let next = if left_hit { left.next() } else { right.next() };
+            let mut intersection = next.expect("intersection");
+            if self.operation.allowed(left_hit, in_left, in_right) {
+                if !left_hit && self.operation == CsgOperation::Difference {
+                    intersection.inverted = !intersection.inverted;
+                }
+                intersections.push(intersection);
+            }
+            if left_hit {
+                in_left = !in_left;
+            } else {
+                in_right = !in_right;
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{BoundingBox, Intersection, Material, Matrix, Ray, Shape, Surface, Transform, Vert, MACHEPS};
+
+#[derive(Clone, Default)]
+pub struct Cube {
+    pub transform: Transform,
+    pub material: Material,
+}
+
+fn check_axis(origin: f64, direction: f64) -> (f64, f64) {
+    let (t_min, t_max) = if direction.abs() >= MACHEPS {
+        ((-1.0 - origin) / direction, (1.0 - origin) / direction)
+    } else {
+        ((-1.0 - origin) * f64::INFINITY, (1.0 - origin) * f64::INFINITY)
+    };
+    if t_min > t_max {
+        (t_max, t_min)
+    } else {
+        (t_min, t_max)
+    }
+}
+
+impl Cube {
+    pub fn new(transform: Matrix) -> Cube {
+        Cube {
+            transform: Transform::new(transform),
+            material: Material::default(),
+        }
+    }
+
+    pub fn intersects_with(&self, ray: &Ray) -> Option<(f64, f64)> {
+        let (x_min, x_max) = check_axis(ray.origin.x, ray.direction.x);
+        let (y_min, y_max) = check_axis(ray.origin.y, ray.direction.y);
+        let (z_min, z_max) = check_axis(ray.origin.z, ray.direction.z);
+        let t_min = x_min.max(y_min).max(z_min);
+        let t_max = x_max.min(y_max).min(z_max);
+        if t_min > t_max {
+            None
+        } else {
+            Some((t_min, t_max))
+        }
+    }
+
+    fn object_normal(point: &Vert) -> Vert {
+        let (x, y, z) = (point.x.abs(), point.y.abs(), point.z.abs());
+        if x >= y && x >= z {
+            Vert::new(point.x, 0.0, 0.0)
+        } else if y >= z {
+            Vert::new(0.0, point.y, 0.0)
+        } else {
+            Vert::new(0.0, 0.0, point.z)
+        }
+    }
+}
+
+impl Shape for Cube {
+    fn bounds(&self) -> BoundingBox {
+        let bounds = BoundingBox {
+            min: Vert::new(-1.0, -1.0, -1.0),
+            max: Vert::new(1.0, 1.0, 1.0),
+        };
+        self.transform.bounds_to_world(&bounds)
+    }
+
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        let (t_min, t_max) = self.intersects_with(&self.transform.ray_to_object(ray))?;
+        let time = if t_min >= 0.0 { t_min } else { t_max };
+        if time >= 0.0 && time < max_time {
+            Some(Intersection::new(time, 0.0, 0.0, self))
+        } else {
+            None
+        }
+    }
+
+    fn all_hits<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
+        if let Some((t_min, t_max)) = self.intersects_with(&self.transform.ray_to_object(ray)) {
+            intersections.push(Intersection::new(t_min, 0.0, 0.0, self));
+            intersections.push(Intersection::new(t_max, 0.0, 0.0, self));
+        }
+    }
+}
+
+impl Surface for Cube {
+    fn norm_vec_at(&self, point: &Vert, _u: f64, _v: f64) -> Vert {
+        let normal = Cube::object_normal(&self.transform.point_to_object(point));
+        self.transform.normal_to_world(&-normal)
+    }
+
+    fn uv_at(&self, point: &Vert, _u: f64, _v: f64) -> Option<(f64, f64)> {
+        let point = self.transform.point_to_object(point);
+        let (x, y, z) = (point.x.abs(), point.y.abs(), point.z.abs());
+        let (u, v) = if x >= y && x >= z {
+            (point.z, point.y)
+        } else if y >= z {
+            (point.x, point.z)
+        } else {
+            (point.x, point.y)
+        };
+        Some(((u + 1.0) / 2.0, (v + 1.0) / 2.0))
+    }
+
+    fn object_point(&self, point: &Vert) -> Vert {
+        self.transform.point_to_object(point)
+    }
+
+    fn uv_density(&self) -> f64 {
+        0.5 * self.transform.object_scale()
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{BoundingBox, Intersection, Material, Matrix, Ray, Shape, Surface, Transform, Vert, MACHEPS, PI};
+
+#[derive(Clone)]
+pub struct Cylinder {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub transform: Transform,
+    pub material: Material,
+}
+
+impl Cylinder {
+    pub fn new(minimum: f64, maximum: f64, transform: Matrix) -> Cylinder {
+        Cylinder {
+            minimum,
+            maximum,
+            transform: Transform::new(transform),
+            material: Material::default(),
+        }
+    }
+
+    pub fn intersects_with<F: FnMut(f64)>(&self, ray: &Ray, mut visit: F) {
+        let a = ray.direction.x.powi(2) + ray.direction.z.powi(2);
+        if a.abs() >= MACHEPS {
+            let b = 2.0 * (ray.origin.x * ray.direction.x + ray.origin.z * ray.direction.z);
+            let c = ray.origin.x.powi(2) + ray.origin.z.powi(2) - 1.0;
+            let discriminant = b.powi(2) - 4.0 * a * c;
+            if discriminant >= 0.0 {
+                let root = discriminant.sqrt();
+                for &time in &[(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)] {
+                    let y = ray.origin.y + time * ray.direction.y;
+                    if self.minimum < y && y < self.maximum {
+                        visit(time);
+                    }
+                }
+            }
+        }
+        if ray.direction.y.abs() >= MACHEPS {
+            for &y in &[self.minimum, self.maximum] {
+                let time = (y - ray.origin.y) / ray.direction.y;
+                let x = ray.origin.x + time * ray.direction.x;
+                let z = ray.origin.z + time * ray.direction.z;
+                if x.powi(2) + z.powi(2) <= 1.0 {
+                    visit(time);
+                }
+            }
+        }
+    }
+
+    fn on_cap(&self, point: &Vert) -> Option<f64> {
+        if point.x.powi(2) + point.z.powi(2) >= 1.0 {
+            None
+        } else if point.y >= self.maximum - MACHEPS {
+            Some(1.0)
+        } else if point.y <= self.minimum + MACHEPS {
+            Some(-1.0)
+        } else {
+            None
+        }
+    }
+}
+
+impl Shape for Cylinder {
+    fn bounds(&self) -> BoundingBox {
+        let bounds = BoundingBox {
+            min: Vert::new(-1.0, self.minimum, -1.0),
+            max: Vert::new(1.0, self.maximum, 1.0),
+        };
+        self.transform.bounds_to_world(&bounds)
+    }
+
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        let mut closest: Option<f64> = None;
+        self.intersects_with(&self.transform.ray_to_object(ray), |time| {
+            if time >= 0.0 && time < closest.unwrap_or(max_time) {
+                closest = Some(time);
+            }
+        });
+        closest.map(|time| Intersection::new(time, 0.0, 0.0, self))
+    }
+
+    fn all_hits<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
+        self.intersects_with(&self.transform.ray_to_object(ray), |time| {
+            intersections.push(Intersection::new(time, 0.0, 0.0, self));
+        });
+    }
+}
+
+impl Surface for Cylinder {
+    fn norm_vec_at(&self, point: &Vert, _u: f64, _v: f64) -> Vert {
+        let point = self.transform.point_to_object(point);
+        let normal = match self.on_cap(&point) {
+            Some(direction) => Vert::new(0.0, direction, 0.0),
+            None => Vert::new(point.x, 0.0, point.z),
+        };
+        self.transform.normal_to_world(&-normal)
+    }
+
+    fn uv_at(&self, point: &Vert, _u: f64, _v: f64) -> Option<(f64, f64)> {
+        let point = self.transform.point_to_object(point);
+        match self.on_cap(&point) {
+            Some(_) => Some(((point.x + 1.0) / 2.0, (point.z + 1.0) / 2.0)),
+            None => {
+                let u = point.x.atan2(point.z) / (2.0 * PI) + 0.5;
+                let v = (point.y - self.minimum) / (self.maximum - self.minimum);
+                Some((u, v))
+            },
+        }
+    }
+
+    fn object_point(&self, point: &Vert) -> Vert {
+        self.transform.point_to_object(point)
+    }
+
+    fn uv_density(&self) -> f64 {
+        let height = self.maximum - self.minimum;
+        let density = if height.is_finite() { (1.0 / height).max(0.5) } else { 1.0 / (2.0 * PI) };
+        density * self.transform.object_scale()
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{BoundingBox, ComputedMesh, Intersection, Matrix, Ray, Shape, Transform};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct Instance {
+    pub mesh: Arc<ComputedMesh>,
+    pub transform: Transform,
+}
+
+impl Instance {
+    pub fn new(mesh: Arc<ComputedMesh>, transform: Matrix) -> Instance {
+        Instance {
+            mesh,
+            transform: Transform::new(transform),
+        }
+    }
+}
+
+impl Shape for Instance {
+    fn bounds(&self) -> BoundingBox {
+        self.transform.bounds_to_world(&self.mesh.bounds())
+    }
+
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        let mut intersection = self.mesh.closest_hit(&self.transform.ray_to_object(ray), max_time)?;
+        intersection.instance = Some(self);
+        Some(intersection)
+    }
+
+    fn any_hit(&self, ray: &Ray, max_time: f64) -> bool {
+        self.mesh.any_hit(&self.transform.ray_to_object(ray), max_time)
+    }
+
+    fn all_hits<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
+        let start = intersections.len();
+        self.mesh.all_hits(&self.transform.ray_to_object(ray), intersections);
+        for intersection in &mut intersections[start..] {
+            intersection.instance = Some(self);
+        }
+    }
+}
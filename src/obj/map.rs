@@ -1,33 +1,147 @@
-use crate::Vert;
+use crate::{Vert, Wrap};
 use image::open;
 
 #[derive(Clone, Debug)]
 pub struct Map {
     image: Vec<Vert>,
+    alpha: Vec<f64>,
+    mipmaps: Vec<Map>,
+    pub opaque: bool,
     pub width: i32,
     pub height: i32,
 }
 
 impl Map {
     pub fn open(path: &str) -> Result<Map, Box<dyn std::error::Error>> {
-        let buffer = open(path)?.to_rgba();
+        let buffer = open(path)?.to_rgba8();
         let width = buffer.width() as i32;
         let height = buffer.height() as i32;
         let rgba = buffer.to_vec();
         let mut image = vec![];
+        let mut alpha = vec![];
         for index in (0..rgba.len()).step_by(4) {
-            let r = rgba[index + 0];
+            let r = rgba[index];
             let g = rgba[index + 1];
             let b = rgba[index + 2];
             image.push(Vert::rgb(r, g, b));
+            alpha.push(rgba[index + 3] as f64 / 255.0);
         }
-        Ok(Map {image, width, height})
+        let opaque = alpha.iter().all(|&alpha| alpha >= 1.0);
+        let mut map = Map {image, alpha, mipmaps: vec![], opaque, width, height};
+        map.mipmaps = map.generate_mipmaps();
+        Ok(map)
+    }
+
+    fn generate_mipmaps(&self) -> Vec<Map> {
+        let mut mipmaps: Vec<Map> = vec![];
+        let (mut width, mut height) = (self.width, self.height);
+        while width > 1 || height > 1 {
+            let next = match mipmaps.last() {
+                Some(last) => last.downsample(),
+                None => self.downsample(),
+            };
+            (width, height) = (next.width, next.height);
+            mipmaps.push(next);
+        }
+        mipmaps
+    }
+
+    fn downsample(&self) -> Map {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut image = vec![];
+        let mut alpha = vec![];
+        for y in 0..height {
+            for x in 0..width {
+                let (x, y) = (x as f64 * 2.0, y as f64 * 2.0);
+                let texels = [(x, y), (x + 1.0, y), (x, y + 1.0), (x + 1.0, y + 1.0)];
+                let colour = texels.iter().fold(Vert::default(), |colour, &(x, y)| colour + self.colour_at(x, y));
+                image.push(colour.multiply_by_scalar(0.25));
+                alpha.push(texels.iter().map(|&(x, y)| self.alpha_at(x, y)).sum::<f64>() * 0.25);
+            }
+        }
+        Map {image, alpha, mipmaps: vec![], opaque: self.opaque, width, height}
+    }
+
+    pub fn levels(&self) -> usize {
+        self.mipmaps.len() + 1
+    }
+
+    pub fn level(&self, level: usize) -> &Map {
+        match level {
+            0 => self,
+            _ => self.mipmaps.get(level - 1).or(self.mipmaps.last()).unwrap_or(self),
+        }
+    }
+
+    pub fn colour_at_uv(&self, u: f64, v: f64, wrap: &Wrap) -> Vert {
+        let x = (u * self.width as f64).floor();
+        let y = ((1.0 - v) * self.height as f64).floor();
+        self.texel_at(x, y, wrap)
+    }
+
+    pub fn bilinear_at_uv(&self, u: f64, v: f64, wrap: &Wrap) -> Vert {
+        let x = u * self.width as f64 - 0.5;
+        let y = (1.0 - v) * self.height as f64 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let top = self.texel_at(x0, y0, wrap).multiply_by_scalar(1.0 - fx) + self.texel_at(x0 + 1.0, y0, wrap).multiply_by_scalar(fx);
+        let bottom = self.texel_at(x0, y0 + 1.0, wrap).multiply_by_scalar(1.0 - fx) + self.texel_at(x0 + 1.0, y0 + 1.0, wrap).multiply_by_scalar(fx);
+        top.multiply_by_scalar(1.0 - fy) + bottom.multiply_by_scalar(fy)
+    }
+
+    pub fn trilinear_at_uv(&self, u: f64, v: f64, footprint: f64, wrap: &Wrap) -> Vert {
+        let texels = footprint * self.width.max(self.height) as f64;
+        if texels <= 1.0 {
+            return self.bilinear_at_uv(u, v, wrap);
+        }
+        let lod = texels.log2().min((self.levels() - 1) as f64);
+        let level = lod.floor() as usize;
+        let fraction = lod - lod.floor();
+        let fine = self.level(level).bilinear_at_uv(u, v, wrap);
+        if fraction <= 0.0 {
+            return fine;
+        }
+        let coarse = self.level(level + 1).bilinear_at_uv(u, v, wrap);
+        fine.multiply_by_scalar(1.0 - fraction) + coarse.multiply_by_scalar(fraction)
     }
 
     pub fn colour_at(&self, x: f64, y: f64) -> Vert {
+        self.image[self.index_of(x, y)].clone()
+    }
+
+    pub fn texel_at(&self, x: f64, y: f64, wrap: &Wrap) -> Vert {
+        match self.wrapped_index_of(x, y, wrap) {
+            Some(index) => self.image[index].clone(),
+            None => match wrap {
+                Wrap::Border(colour) => colour.clone(),
+                _ => Vert::default(),
+            },
+        }
+    }
+
+    pub fn alpha_at_uv(&self, u: f64, v: f64, wrap: &Wrap) -> f64 {
+        let x = (u * self.width as f64).floor();
+        let y = ((1.0 - v) * self.height as f64).floor();
+        match self.wrapped_index_of(x, y, wrap) {
+            Some(index) => self.alpha[index],
+            None => 1.0,
+        }
+    }
+
+    pub fn alpha_at(&self, x: f64, y: f64) -> f64 {
+        self.alpha[self.index_of(x, y)]
+    }
+
+    fn index_of(&self, x: f64, y: f64) -> usize {
         let x = x.min(self.width as f64 - 1.0).max(0.0) as i32;
         let y = y.min(self.height as f64 - 1.0).max(0.0) as i32;
-        let index = y * self.width + x;
-        self.image[index as usize].clone()
+        (y * self.width + x) as usize
+    }
+
+    fn wrapped_index_of(&self, x: f64, y: f64, wrap: &Wrap) -> Option<usize> {
+        let x = wrap.texel(x as i32, self.width)?;
+        let y = wrap.texel(y as i32, self.height)?;
+        Some((y * self.width + x) as usize)
     }
 }
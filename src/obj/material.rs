@@ -1,22 +1,112 @@
-use crate::Vert;
+use crate::{Pattern, Phong, ShadingModel, Texture, Vert};
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Material {
     pub colour: Vert,
+    pub shading: Arc<dyn ShadingModel>,
+    pub pattern: Option<Arc<Pattern>>,
+    pub map: Option<Texture>,
+    pub specular_map: Option<Texture>,
+    pub shininess_map: Option<Texture>,
+    pub alpha_map: Option<Texture>,
+    pub bump_map: Option<Texture>,
+    pub normal_map: Option<Texture>,
+    pub emission_map: Option<Texture>,
     pub ambient: f64,
     pub diffuse: f64,
     pub specular: f64,
     pub shininess: f64,
+    pub reflective: f64,
+    pub transparency: f64,
+    pub alpha_cutoff: Option<f64>,
+    pub refractive_index: f64,
+    pub emission: Vert,
+    pub illumination: usize,
+}
+
+impl Material {
+    pub fn colour_at(&self, point: &Vert, uv: Option<(f64, f64)>, footprint: f64) -> Vert {
+        if let Some(pattern) = &self.pattern {
+            return pattern.colour_at(point, uv);
+        }
+        match (&self.map, uv) {
+            (Some(map), Some((u, v))) => map.sample(u, v, footprint),
+            _ => self.colour.clone(),
+        }
+    }
+
+    pub fn specular_at(&self, uv: Option<(f64, f64)>) -> f64 {
+        match (&self.specular_map, uv) {
+            (Some(map), Some((u, v))) => self.specular * map.value_at_uv(u, v),
+            _ => self.specular,
+        }
+    }
+
+    pub fn shininess_at(&self, uv: Option<(f64, f64)>) -> f64 {
+        match (&self.shininess_map, uv) {
+            (Some(map), Some((u, v))) => self.shininess * map.value_at_uv(u, v),
+            _ => self.shininess,
+        }
+    }
+
+    pub fn transparency_at(&self, uv: Option<(f64, f64)>) -> f64 {
+        match (self.alpha_cutoff, uv) {
+            (None, Some((u, v))) if self.has_alpha() => 1.0 - (1.0 - self.transparency) * self.alpha_at(u, v),
+            _ => self.transparency,
+        }
+    }
+
+    pub fn has_alpha(&self) -> bool {
+        self.map.as_ref().is_some_and(|map| !map.map.opaque) || self.alpha_map.is_some()
+    }
+
+    pub fn alpha_at(&self, u: f64, v: f64) -> f64 {
+        let alpha = self.map.as_ref().map_or(1.0, |map| map.alpha_at_uv(u, v));
+        match &self.alpha_map {
+            Some(alpha_map) => alpha * alpha_map.value_at_uv(u, v),
+            None => alpha,
+        }
+    }
+
+    pub fn cut_out_at(&self, u: f64, v: f64) -> bool {
+        match self.alpha_cutoff {
+            Some(cutoff) => self.has_alpha() && self.alpha_at(u, v) < cutoff,
+            None => false,
+        }
+    }
+
+    pub fn emission_at(&self, uv: Option<(f64, f64)>) -> Vert {
+        match (&self.emission_map, uv) {
+            (Some(map), Some((u, v))) => self.emission.clone() * map.colour_at_uv(u, v),
+            _ => self.emission.clone(),
+        }
+    }
 }
 
 impl Default for Material {
     fn default() -> Material {
         Material {
             colour: Vert::rgb(190, 190, 190),
+            shading: Arc::new(Phong),
+            pattern: None,
+            map: None,
+            specular_map: None,
+            shininess_map: None,
+            alpha_map: None,
+            bump_map: None,
+            normal_map: None,
+            emission_map: None,
             ambient: 0.25,
             diffuse: 1.0,
             specular: 0.0,
             shininess: 0.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            alpha_cutoff: Some(0.5),
+            refractive_index: 1.0,
+            emission: Vert::default(),
+            illumination: 2,
         }
     }
 }
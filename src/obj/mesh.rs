@@ -1,6 +1,6 @@
 use crate::{
-    ComputeTriangles, ComputedMesh, Map, Matrix, TransformNormals, TransformTriangles, Triangle,
-    Vert,
+    ComputeTriangles, ComputedMesh, ComputedTriangle, Map, Matrix, TransformNormals, TransformTriangles,
+    Triangle, Vert,
 };
 use super::WavefrontObj;
 use std::sync::Arc;
@@ -17,11 +17,23 @@ pub struct Mesh {
 
 impl Mesh {
     pub fn load_obj(path: &str) -> Result<Mesh, Box<dyn std::error::Error>> {
-        let obj = Mesh::from(WavefrontObj::open(path)?);
+        Mesh::load_obj_with_maps(path, &mut HashMap::new())
+    }
+
+    pub fn load_obj_with_maps(path: &str, maps: &mut HashMap<String, Arc<Map>>) -> Result<Mesh, Box<dyn std::error::Error>> {
+        let obj = Mesh::from(WavefrontObj::open(path, maps)?);
         Ok(obj)
     }
 
     pub fn compute(&self) -> ComputedMesh {
+        ComputedMesh::new(self.compute_triangles())
+    }
+
+    pub fn refit(&self, computed_mesh: &ComputedMesh) -> ComputedMesh {
+        computed_mesh.refit(self.compute_triangles())
+    }
+
+    fn compute_triangles(&self) -> Vec<ComputedTriangle> {
         let mut triangles = self.triangles.clone();
         if self.rotation.x != 0.0 {
             let matrix = Matrix::rot_x(self.rotation.x);
@@ -44,7 +56,6 @@ impl Mesh {
         if self.scale.x != 0.0 || self.scale.y != 0.0 || self.scale.z != 0.0 {
             triangles.transform_triangles(Matrix::scale(self.scale.x, self.scale.y, self.scale.z));
         }
-        let triangles = triangles.compute_triangles();
-        ComputedMesh {triangles}
+        triangles.compute_triangles()
     }
 }
@@ -1,16 +1,44 @@
+mod bounding_box;
+mod bvh;
 mod computed_mesh;
 mod computed_triangle;
+mod cone;
+mod cube;
+mod csg;
+mod cylinder;
+mod instance;
 mod material;
 mod mesh;
 mod map;
+mod noise;
+mod pattern;
+mod plane;
+mod shape;
+mod sphere;
+mod surface;
+mod texture;
 mod triangle;
 mod uv_map;
 mod wavefront_obj;
-pub use computed_mesh::{ComputedMesh, ComputeMeshes};
+pub use bounding_box::BoundingBox;
+pub use bvh::Bvh;
+pub use computed_mesh::ComputedMesh;
 pub use computed_triangle::{ComputedTriangle, ComputeTriangles};
+pub use cone::Cone;
+pub use cube::Cube;
+pub use csg::{Csg, CsgOperation};
+pub use cylinder::Cylinder;
+pub use instance::Instance;
 pub use material::Material;
 pub use mesh::Mesh;
 pub use map::Map;
+pub use noise::Noise;
+pub use pattern::{Pattern, PatternKind, PatternSpace};
+pub use plane::Plane;
+pub use shape::Shape;
+pub use sphere::Sphere;
+pub use surface::Surface;
+pub use texture::{Filter, Texture, Wrap};
 pub use triangle::{Triangle, TransformNormals, TransformTriangles};
 pub use uv_map::UVMap;
 use wavefront_obj::WavefrontObj;
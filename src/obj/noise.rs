@@ -0,0 +1,101 @@
+use crate::Vert;
+
+#[derive(Clone)]
+pub struct Noise {
+    permutation: Vec<usize>,
+}
+
+impl Noise {
+    pub fn new(seed: u64) -> Noise {
+        let mut state = seed;
+        let mut table: Vec<usize> = (0..256).collect();
+        for index in (1..256).rev() {
+            let swap = (splitmix(&mut state) % (index as u64 + 1)) as usize;
+            table.swap(index, swap);
+        }
+        let permutation = table.iter().chain(table.iter()).copied().collect();
+        Noise {permutation}
+    }
+
+    pub fn noise(&self, point: &Vert) -> f64 {
+        let (x0, y0, z0) = (point.x.floor(), point.y.floor(), point.z.floor());
+        let (x, y, z) = (point.x - x0, point.y - y0, point.z - z0);
+        let (xi, yi, zi) = ((x0 as i64 & 255) as usize, (y0 as i64 & 255) as usize, (z0 as i64 & 255) as usize);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+        let p = &self.permutation;
+        let a = p[xi] + yi;
+        let aa = p[a] + zi;
+        let ab = p[a + 1] + zi;
+        let b = p[xi + 1] + yi;
+        let ba = p[b] + zi;
+        let bb = p[b + 1] + zi;
+        lerp(w,
+            lerp(v,
+                lerp(u, grad(p[aa], x, y, z), grad(p[ba], x - 1.0, y, z)),
+                lerp(u, grad(p[ab], x, y - 1.0, z), grad(p[bb], x - 1.0, y - 1.0, z))),
+            lerp(v,
+                lerp(u, grad(p[aa + 1], x, y, z - 1.0), grad(p[ba + 1], x - 1.0, y, z - 1.0)),
+                lerp(u, grad(p[ab + 1], x, y - 1.0, z - 1.0), grad(p[bb + 1], x - 1.0, y - 1.0, z - 1.0))))
+    }
+
+    pub fn fbm(&self, point: &Vert, octaves: usize) -> f64 {
+        let mut total = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = 1.0;
+        for _ in 0..octaves {
+            total += self.noise(&point.multiply_by_scalar(frequency)) * amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        total
+    }
+
+    pub fn turbulence(&self, point: &Vert, octaves: usize) -> f64 {
+        let mut total = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = 1.0;
+        for _ in 0..octaves {
+            total += self.noise(&point.multiply_by_scalar(frequency)).abs() * amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        total
+    }
+
+    pub fn jitter(&self, point: &Vert) -> Vert {
+        Vert::new(
+            self.noise(point),
+            self.noise(&(point.clone() + Vert::new(31.7, 17.3, 5.9))),
+            self.noise(&(point.clone() + Vert::new(-11.1, 43.9, 23.3))),
+        )
+    }
+}
+
+fn splitmix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
@@ -0,0 +1,121 @@
+use crate::{Noise, Transform, Vert};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub enum PatternKind {
+    Solid(Vert),
+    Stripes(Arc<Pattern>, Arc<Pattern>),
+    Checker(Arc<Pattern>, Arc<Pattern>),
+    Gradient(Arc<Pattern>, Arc<Pattern>),
+    Rings(Arc<Pattern>, Arc<Pattern>),
+    GradientNoise(Noise, Arc<Pattern>, Arc<Pattern>),
+    Fbm(Noise, usize, Arc<Pattern>, Arc<Pattern>),
+    Turbulence(Noise, usize, Arc<Pattern>, Arc<Pattern>),
+    Perturbed(Noise, f64, Arc<Pattern>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PatternSpace {
+    Object,
+    Uv,
+}
+
+#[derive(Clone)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub space: PatternSpace,
+    pub transform: Transform,
+}
+
+impl Pattern {
+    pub fn new(kind: PatternKind) -> Pattern {
+        Pattern {
+            kind,
+            space: PatternSpace::Object,
+            transform: Transform::default(),
+        }
+    }
+
+    pub fn solid(colour: Vert) -> Pattern {
+        Pattern::new(PatternKind::Solid(colour))
+    }
+
+    pub fn stripes(a: Pattern, b: Pattern) -> Pattern {
+        Pattern::new(PatternKind::Stripes(Arc::new(a), Arc::new(b)))
+    }
+
+    pub fn checker(a: Pattern, b: Pattern) -> Pattern {
+        Pattern::new(PatternKind::Checker(Arc::new(a), Arc::new(b)))
+    }
+
+    pub fn gradient(a: Pattern, b: Pattern) -> Pattern {
+        Pattern::new(PatternKind::Gradient(Arc::new(a), Arc::new(b)))
+    }
+
+    pub fn rings(a: Pattern, b: Pattern) -> Pattern {
+        Pattern::new(PatternKind::Rings(Arc::new(a), Arc::new(b)))
+    }
+
+    pub fn noise(seed: u64, a: Pattern, b: Pattern) -> Pattern {
+        Pattern::new(PatternKind::GradientNoise(Noise::new(seed), Arc::new(a), Arc::new(b)))
+    }
+
+    pub fn fbm(seed: u64, octaves: usize, a: Pattern, b: Pattern) -> Pattern {
+        Pattern::new(PatternKind::Fbm(Noise::new(seed), octaves, Arc::new(a), Arc::new(b)))
+    }
+
+    pub fn turbulence(seed: u64, octaves: usize, a: Pattern, b: Pattern) -> Pattern {
+        Pattern::new(PatternKind::Turbulence(Noise::new(seed), octaves, Arc::new(a), Arc::new(b)))
+    }
+
+    pub fn perturbed(seed: u64, amount: f64, pattern: Pattern) -> Pattern {
+        Pattern::new(PatternKind::Perturbed(Noise::new(seed), amount, Arc::new(pattern)))
+    }
+
+    pub fn colour_at(&self, point: &Vert, uv: Option<(f64, f64)>) -> Vert {
+        let local = match (self.space, uv) {
+            (PatternSpace::Uv, Some((u, v))) => self.transform.point_to_object(&Vert::new(u, v, 0.0)),
+            _ => self.transform.point_to_object(point),
+        };
+        match &self.kind {
+            PatternKind::Solid(colour) => colour.clone(),
+            PatternKind::Stripes(a, b) => {
+                if local.x.floor() as i64 % 2 == 0 {
+                    a.colour_at(point, uv)
+                } else {
+                    b.colour_at(point, uv)
+                }
+            },
+            PatternKind::Checker(a, b) => {
+                if (local.x.floor() + local.y.floor() + local.z.floor()) as i64 % 2 == 0 {
+                    a.colour_at(point, uv)
+                } else {
+                    b.colour_at(point, uv)
+                }
+            },
+            PatternKind::Gradient(a, b) => blend(a, b, local.x - local.x.floor(), point, uv),
+            PatternKind::Rings(a, b) => {
+                if (local.x.powi(2) + local.z.powi(2)).sqrt().floor() as i64 % 2 == 0 {
+                    a.colour_at(point, uv)
+                } else {
+                    b.colour_at(point, uv)
+                }
+            },
+            PatternKind::GradientNoise(noise, a, b) => blend(a, b, 0.5 + 0.5 * noise.noise(&local), point, uv),
+            PatternKind::Fbm(noise, octaves, a, b) => blend(a, b, 0.5 + noise.fbm(&local, *octaves), point, uv),
+            PatternKind::Turbulence(noise, octaves, a, b) => blend(a, b, noise.turbulence(&local, *octaves), point, uv),
+            PatternKind::Perturbed(noise, amount, pattern) => {
+                let jitter = noise.jitter(&local).multiply_by_scalar(*amount);
+                let uv = uv.map(|(u, v)| (u + jitter.x, v + jitter.y));
+                pattern.colour_at(&(point.clone() + jitter), uv)
+            },
+        }
+    }
+}
+
+fn blend(a: &Pattern, b: &Pattern, fraction: f64, point: &Vert, uv: Option<(f64, f64)>) -> Vert {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let a = a.colour_at(point, uv);
+    let b = b.colour_at(point, uv);
+    a.clone() + (b - a).multiply_by_scalar(fraction)
+}
@@ -0,0 +1,77 @@
+use crate::{BoundingBox, Intersection, Material, Ray, Shape, Surface, Vert, MACHEPS};
+
+#[derive(Clone)]
+pub struct Plane {
+    pub position: Vert,
+    pub normal: Vert,
+    pub material: Material,
+}
+
+impl Plane {
+    pub fn new(position: Vert, normal: Vert) -> Plane {
+        Plane {
+            position,
+            normal: normal.normalise(),
+            material: Material::default(),
+        }
+    }
+
+    pub fn intersects_with(&self, ray: &Ray) -> Option<f64> {
+        let denominator = ray.direction.dot_product(&self.normal);
+        if denominator.abs() < MACHEPS {
+            return None;
+        }
+        let time = (self.position.clone() - ray.origin.clone()).dot_product(&self.normal) / denominator;
+        Some(time)
+    }
+}
+
+impl Shape for Plane {
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox {
+            min: Vert::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            max: Vert::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        }
+    }
+
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        match self.intersects_with(ray) {
+            Some(time) if time >= 0.0 && time < max_time => Some(Intersection::new(time, 0.0, 0.0, self)),
+            _ => None,
+        }
+    }
+
+    fn all_hits<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
+        if let Some(time) = self.intersects_with(ray) {
+            intersections.push(Intersection::new(time, 0.0, 0.0, self));
+        }
+    }
+}
+
+impl Surface for Plane {
+    fn norm_vec_at(&self, _point: &Vert, _u: f64, _v: f64) -> Vert {
+        -self.normal.clone()
+    }
+
+    fn uv_at(&self, point: &Vert, _u: f64, _v: f64) -> Option<(f64, f64)> {
+        let axis = if self.normal.x.abs() > 0.9 {
+            Vert::new(0.0, 1.0, 0.0)
+        } else {
+            Vert::new(1.0, 0.0, 0.0)
+        };
+        let u_axis = (axis.clone() - self.normal.multiply_by_scalar(axis.dot_product(&self.normal))).normalise();
+        let v_axis = u_axis.cross_product(&self.normal);
+        let offset = point.clone() - self.position.clone();
+        let u = offset.dot_product(&u_axis);
+        let v = offset.dot_product(&v_axis);
+        Some((u, v))
+    }
+
+    fn uv_density(&self) -> f64 {
+        1.0
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+}
@@ -0,0 +1,17 @@
+use crate::{BoundingBox, Intersection, Ray};
+
+pub trait Shape: Send + Sync {
+    fn bounds(&self) -> BoundingBox;
+
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>>;
+
+    fn any_hit(&self, ray: &Ray, max_time: f64) -> bool {
+        self.closest_hit(ray, max_time).is_some()
+    }
+
+    fn all_hits<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
+        if let Some(intersection) = self.closest_hit(ray, f64::INFINITY) {
+            intersections.push(intersection);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{BoundingBox, Intersection, Material, Ray, Shape, Surface, Vert, PI};
+
+#[derive(Clone)]
+pub struct Sphere {
+    pub centre: Vert,
+    pub radius: f64,
+    pub material: Material,
+}
+
+impl Sphere {
+    pub fn new(centre: Vert, radius: f64) -> Sphere {
+        Sphere {
+            centre,
+            radius,
+            material: Material::default(),
+        }
+    }
+
+    pub fn intersects_with(&self, ray: &Ray) -> Option<(f64, f64)> {
+        let centre_to_origin = ray.origin.clone() - self.centre.clone();
+        let a = ray.direction.dot_product(&ray.direction);
+        let b = 2.0 * ray.direction.dot_product(&centre_to_origin);
+        let c = centre_to_origin.dot_product(&centre_to_origin) - self.radius.powi(2);
+        let discriminant = b.powi(2) - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        Some(((-b - root) / (2.0 * a), (-b + root) / (2.0 * a)))
+    }
+}
+
+impl Shape for Sphere {
+    fn bounds(&self) -> BoundingBox {
+        let extent = Vert::new(self.radius, self.radius, self.radius);
+        BoundingBox {
+            min: self.centre.clone() - extent.clone(),
+            max: self.centre.clone() + extent,
+        }
+    }
+
+    fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        let (near, far) = self.intersects_with(ray)?;
+        let time = if near >= 0.0 { near } else { far };
+        if time >= 0.0 && time < max_time {
+            Some(Intersection::new(time, 0.0, 0.0, self))
+        } else {
+            None
+        }
+    }
+
+    fn all_hits<'a>(&'a self, ray: &Ray, intersections: &mut Vec<Intersection<'a>>) {
+        if let Some((near, far)) = self.intersects_with(ray) {
+            intersections.push(Intersection::new(near, 0.0, 0.0, self));
+            intersections.push(Intersection::new(far, 0.0, 0.0, self));
+        }
+    }
+}
+
+impl Surface for Sphere {
+    fn norm_vec_at(&self, point: &Vert, _u: f64, _v: f64) -> Vert {
+        (self.centre.clone() - point.clone()).normalise()
+    }
+
+    fn uv_at(&self, point: &Vert, _u: f64, _v: f64) -> Option<(f64, f64)> {
+        let direction = (point.clone() - self.centre.clone()).multiply_by_scalar(1.0 / self.radius);
+        let theta = direction.x.atan2(direction.z);
+        let phi = direction.y.clamp(-1.0, 1.0).acos();
+        let u = 1.0 - (theta / (2.0 * PI) + 0.5);
+        let v = 1.0 - phi / PI;
+        Some((u, v))
+    }
+
+    fn object_point(&self, point: &Vert) -> Vert {
+        (point.clone() - self.centre.clone()).multiply_by_scalar(1.0 / self.radius)
+    }
+
+    fn uv_density(&self) -> f64 {
+        1.0 / (2.0 * PI * self.radius)
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+}
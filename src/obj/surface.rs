@@ -0,0 +1,17 @@
+use crate::{Material, Vert};
+
+pub trait Surface: Send + Sync {
+    fn norm_vec_at(&self, point: &Vert, u: f64, v: f64) -> Vert;
+
+    fn uv_at(&self, point: &Vert, u: f64, v: f64) -> Option<(f64, f64)>;
+
+    fn object_point(&self, point: &Vert) -> Vert {
+        point.clone()
+    }
+
+    fn uv_density(&self) -> f64 {
+        0.0
+    }
+
+    fn material(&self) -> &Material;
+}
@@ -0,0 +1,92 @@
+use crate::{Map, Vert};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Bilinear,
+    Trilinear,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Wrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    Border(Vert),
+}
+
+impl Wrap {
+    pub fn texel(&self, index: i32, size: i32) -> Option<i32> {
+        match self {
+            Wrap::Repeat => Some(index.rem_euclid(size)),
+            Wrap::MirroredRepeat => {
+                let index = index.rem_euclid(2 * size);
+                Some(if index < size { index } else { 2 * size - 1 - index })
+            },
+            Wrap::ClampToEdge => Some(index.clamp(0, size - 1)),
+            Wrap::Border(_) => (0..size).contains(&index).then_some(index),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Texture {
+    pub map: Arc<Map>,
+    pub offset: (f64, f64),
+    pub scale: (f64, f64),
+    pub bump_multiplier: f64,
+    pub wrap: Wrap,
+    pub filter: Filter,
+}
+
+impl Texture {
+    pub fn new(map: Arc<Map>) -> Texture {
+        Texture {
+            map,
+            offset: (0.0, 0.0),
+            scale: (1.0, 1.0),
+            bump_multiplier: 1.0,
+            wrap: Wrap::Repeat,
+            filter: Filter::Trilinear,
+        }
+    }
+
+    pub fn colour_at_uv(&self, u: f64, v: f64) -> Vert {
+        self.sample(u, v, 0.0)
+    }
+
+    pub fn sample(&self, u: f64, v: f64, footprint: f64) -> Vert {
+        let (u, v) = self.map_uv(u, v);
+        match self.filter {
+            Filter::Nearest => self.map.colour_at_uv(u, v, &self.wrap),
+            Filter::Bilinear => self.map.bilinear_at_uv(u, v, &self.wrap),
+            Filter::Trilinear => self.map.trilinear_at_uv(u, v, footprint * self.scale.0.abs().max(self.scale.1.abs()), &self.wrap),
+        }
+    }
+
+    pub fn alpha_at_uv(&self, u: f64, v: f64) -> f64 {
+        let (u, v) = self.map_uv(u, v);
+        self.map.alpha_at_uv(u, v, &self.wrap)
+    }
+
+    fn map_uv(&self, u: f64, v: f64) -> (f64, f64) {
+        let u = u * self.scale.0 + self.offset.0;
+        let v = v * self.scale.1 + self.offset.1;
+        (u, v)
+    }
+
+    pub fn value_at_uv(&self, u: f64, v: f64) -> f64 {
+        let colour = self.colour_at_uv(u, v);
+        (colour.x + colour.y + colour.z) / 3.0
+    }
+
+    pub fn gradient_at_uv(&self, u: f64, v: f64) -> (f64, f64) {
+        let step_u = 1.0 / (self.map.width as f64 * self.scale.0);
+        let step_v = 1.0 / (self.map.height as f64 * self.scale.1);
+        let height = self.value_at_uv(u, v);
+        let du = self.value_at_uv(u + step_u, v) - height;
+        let dv = self.value_at_uv(u, v + step_v) - height;
+        (du * self.bump_multiplier, dv * self.bump_multiplier)
+    }
+}
@@ -6,6 +6,7 @@ pub struct Triangle {
     pub p2: Vert,
     pub p3: Vert,
     pub normals: Option<(Vert, Vert, Vert)>,
+    pub tangents: Option<(Vert, Vert, Vert)>,
     pub material: Material,
     pub uv_map: Option<UVMap>,
 }
@@ -17,6 +18,7 @@ impl Triangle {
             self.p2.clone(),
             self.p3.clone(),
             self.normals.clone(),
+            self.tangents.clone(),
             self.material.clone(),
             self.uv_map.clone(),
         )
@@ -31,13 +33,22 @@ impl Triangle {
     pub fn transform_normals(&mut self, transform: &Matrix) {
         self.normals = match &self.normals {
             Some((n1, n2, n3)) => {
-                let n1 = transform.multiply_with_vert(&n1);
-                let n2 = transform.multiply_with_vert(&n2);
-                let n3 = transform.multiply_with_vert(&n3);
+                let n1 = transform.multiply_with_vert(n1);
+                let n2 = transform.multiply_with_vert(n2);
+                let n3 = transform.multiply_with_vert(n3);
                 Some((n1, n2, n3))
             }
             None => None,
         };
+        self.tangents = match &self.tangents {
+            Some((t1, t2, t3)) => {
+                let t1 = transform.multiply_with_vert(t1);
+                let t2 = transform.multiply_with_vert(t2);
+                let t3 = transform.multiply_with_vert(t3);
+                Some((t1, t2, t3))
+            }
+            None => None,
+        };
     }
 }
 
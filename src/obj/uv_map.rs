@@ -1,19 +1,29 @@
-use crate::{Map, Vert};
-use std::sync::Arc;
+use crate::{Vert, MACHEPS};
 
 #[derive(Clone, Debug)]
 pub struct UVMap {
-    pub map: Arc<Map>,
     pub t1: Vert,
     pub t2: Vert,
     pub t3: Vert,
 }
 
 impl UVMap {
-    pub fn colour_at(&self, u: f64, v: f64) -> Vert {
+    pub fn uv_at(&self, u: f64, v: f64) -> (f64, f64) {
         let coord = self.t1.clone() + (self.t2.clone() - self.t1.clone()).multiply_by_scalar(u) + (self.t3.clone() - self.t1.clone()).multiply_by_scalar(v);
-        let x = (coord.x * (self.map.width - 1) as f64).floor();
-        let y = ((1.0 - coord.y) * (self.map.height - 1) as f64).floor();
-        self.map.colour_at(x, y)
+        (coord.x, coord.y)
+    }
+
+    pub fn tangent(&self, e1: &Vert, e2: &Vert) -> Option<(Vert, f64)> {
+        let d1 = self.t2.clone() - self.t1.clone();
+        let d2 = self.t3.clone() - self.t1.clone();
+        let det = d1.x * d2.y - d2.x * d1.y;
+        if det.abs() < MACHEPS {
+            return None;
+        }
+        let tangent = (e1.multiply_by_scalar(d2.y) - e2.multiply_by_scalar(d1.y)).multiply_by_scalar(1.0 / det);
+        let bitangent = (e2.multiply_by_scalar(d1.x) - e1.multiply_by_scalar(d2.x)).multiply_by_scalar(1.0 / det);
+        let outward = e1.cross_product(e2);
+        let handedness = if outward.cross_product(&tangent).dot_product(&bitangent) < 0.0 { -1.0 } else { 1.0 };
+        Some((tangent.normalise(), handedness))
     }
 }
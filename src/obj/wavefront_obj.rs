@@ -1,4 +1,4 @@
-use crate::{Material, Map, Mesh, Triangle, UVMap, Vert};
+use crate::{Material, Map, Mesh, MetallicRoughness, Texture, Triangle, UVMap, Vert, Wrap, MACHEPS};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs::File;
@@ -10,8 +10,71 @@ use std::sync::Arc;
 
 #[derive(Debug, Default)]
 pub struct MtlDefinition {
+    pub ambient: Option<Vert>,
     pub diffusion: Option<Vert>,
-    pub map: Option<Arc<Map>>,
+    pub specular: Option<Vert>,
+    pub emission: Option<Vert>,
+    pub shininess: Option<f64>,
+    pub dissolve: Option<f64>,
+    pub refractive_index: Option<f64>,
+    pub illumination: Option<usize>,
+    pub metallic: Option<f64>,
+    pub roughness: Option<f64>,
+    pub map: Option<Texture>,
+    pub specular_map: Option<Texture>,
+    pub shininess_map: Option<Texture>,
+    pub alpha_map: Option<Texture>,
+    pub bump_map: Option<Texture>,
+    pub normal_map: Option<Texture>,
+    pub emission_map: Option<Texture>,
+}
+
+impl MtlDefinition {
+    pub fn material(&self) -> Material {
+        let mut material = Material::default();
+        if let Some(ambient) = &self.ambient {
+            material.ambient = strength(ambient);
+        }
+        if let Some(diffusion) = &self.diffusion {
+            material.colour = diffusion.clone();
+        }
+        if let Some(specular) = &self.specular {
+            material.specular = strength(specular);
+        }
+        if let Some(emission) = &self.emission {
+            material.emission = emission.clone();
+        }
+        if let Some(shininess) = self.shininess {
+            material.shininess = shininess;
+        }
+        if let Some(dissolve) = self.dissolve {
+            material.transparency = 1.0 - dissolve;
+        }
+        if let Some(refractive_index) = self.refractive_index {
+            material.refractive_index = refractive_index;
+        }
+        if let Some(illumination) = self.illumination {
+            material.illumination = illumination;
+        }
+        if self.metallic.is_some() || self.roughness.is_some() {
+            material.shading = Arc::new(MetallicRoughness {
+                metallic: self.metallic.unwrap_or(0.0),
+                roughness: self.roughness.unwrap_or(0.5),
+            });
+        }
+        material.map = self.map.clone();
+        material.specular_map = self.specular_map.clone();
+        material.shininess_map = self.shininess_map.clone();
+        material.alpha_map = self.alpha_map.clone();
+        material.bump_map = self.bump_map.clone();
+        material.normal_map = self.normal_map.clone();
+        material.emission_map = self.emission_map.clone();
+        material
+    }
+}
+
+fn strength(colour: &Vert) -> f64 {
+    (colour.x + colour.y + colour.z) / 3.0
 }
 
 #[derive(Default)]
@@ -23,6 +86,8 @@ pub struct WavefrontObj {
 #[derive(Debug)]
 struct VertIndex {x: usize, y: usize, z: usize}
 
+type TangentKey = (usize, usize, Option<usize>, bool);
+
 #[derive(Debug)]
 pub struct Face {
     v: VertIndex,
@@ -57,6 +122,26 @@ fn expect_f64(words: &mut SplitWhitespace) -> Result<f64, FileError> {
     }
 }
 
+fn expect_usize_word(words: &mut SplitWhitespace) -> Result<usize, FileError> {
+    match words.next() {
+        Some(str) => {
+            match str.parse::<usize>() {
+                Ok(value) => Ok(value),
+                Err(_)=> Err(FileError {}),
+            }
+        }
+        None => Err(FileError {}),
+    }
+}
+
+fn expect_dissolve(words: &mut SplitWhitespace) -> Result<f64, FileError> {
+    match words.next() {
+        Some("-halo") => expect_f64(words),
+        Some(str) => str.parse::<f64>().map_err(|_| FileError {}),
+        None => Err(FileError {}),
+    }
+}
+
 fn expect_xy(words: &mut SplitWhitespace) -> Result<Vert, FileError> {
     let x= expect_f64(words)?;
     let y= expect_f64(words)?;
@@ -86,11 +171,11 @@ fn expect_usize(split: &mut Split<char>) -> Result<usize, FileError> {
 
 fn maybe_usize(split: &mut Split<char>) -> Result<Option<usize>, FileError> {
     match split.next() {
-        Some(str) if str.is_empty() => Ok(None),
+        Some("") => Ok(None),
         Some(str) => {
             match str.parse::<usize>() {
                 Ok(value) => Ok(Some(value)),
-                Err(_)=> return Err(FileError {}),
+                Err(_)=> Err(FileError {}),
             }
         }
         None => Ok(None),
@@ -118,27 +203,15 @@ fn expect_face(words: &mut SplitWhitespace, usemtl: Option<String>) -> Result<Fa
         Some(str) => expect_vert_face_definition(str)?,
         None => return Err(FileError {}),
     };
-    let vt = if v1.t.is_some() && v2.t.is_some() && v3.t.is_some()  {
-        Some(VertIndex {
-            x: v1.t.expect("value"),
-            y: v2.t.expect("value"),
-            z: v3.t.expect("value"),
-        })
-    } else if v1.t.is_none() && v2.t.is_none() && v3.t.is_none() {
-        None
-    } else {
-        return Err(FileError {});
+    let vt = match (v1.t, v2.t, v3.t) {
+        (Some(x), Some(y), Some(z)) => Some(VertIndex {x, y, z}),
+        (None, None, None) => None,
+        _ => return Err(FileError {}),
     };
-    let vn = if v1.n.is_some() && v2.n.is_some() && v3.n.is_some()  {
-        Some(VertIndex {
-            x: v1.n.expect("value"),
-            y: v2.n.expect("value"),
-            z: v3.n.expect("value"),
-        })
-    } else if v1.n.is_none() && v2.n.is_none() && v3.n.is_none() {
-        None
-    } else {
-        return Err(FileError {});
+    let vn = match (v1.n, v2.n, v3.n) {
+        (Some(x), Some(y), Some(z)) => Some(VertIndex {x, y, z}),
+        (None, None, None) => None,
+        _ => return Err(FileError {}),
     };
     let face = Face {
         v: VertIndex {
@@ -153,12 +226,12 @@ fn expect_face(words: &mut SplitWhitespace, usemtl: Option<String>) -> Result<Fa
     Ok(face)
 }
 
-fn expect_mtl(words: &mut SplitWhitespace, parent: &Path) -> Result<WavefrontMtl, Box<dyn Error>> {
+fn expect_mtl(words: &mut SplitWhitespace, parent: &Path, maps: &mut HashMap<String, Arc<Map>>) -> Result<WavefrontMtl, Box<dyn Error>> {
     let mtl_file_string = expect_string(words)?;
     let joined_path = parent.join(mtl_file_string);
     match joined_path.to_str() {
         Some(joined_path_str) => {
-            let mtl = WavefrontMtl::open(joined_path_str)?;
+            let mtl = WavefrontMtl::open(joined_path_str, maps)?;
             Ok(mtl)
         },
         None => Err(Box::new(FileError {})),
@@ -181,7 +254,7 @@ impl std::error::Error for FileError {
 }
 
 impl WavefrontObj {
-    pub fn open(path: &str) -> Result<Self, Box<dyn Error>> {
+    pub fn open(path: &str, maps: &mut HashMap<String, Arc<Map>>) -> Result<Self, Box<dyn Error>> {
         let file = BufReader::new(File::open(path)?);
         let mut usemtl = None;
         let mut v = Vec::new();
@@ -195,7 +268,7 @@ impl WavefrontObj {
             match words.next() {
                 Some("mtllib") => {
                     mtllib = match Path::new(path).parent() {
-                        Some(parent) => expect_mtl(&mut words, parent)?,
+                        Some(parent) => expect_mtl(&mut words, parent, maps)?,
                         _ => return Err(Box::new(FileError {})),
                     };
                 },
@@ -208,33 +281,22 @@ impl WavefrontObj {
             }
         }
         let mut obj = WavefrontObj::default();
+        let mut tangents: HashMap<TangentKey, Vert> = HashMap::new();
+        let mut corners = vec![];
         for face in f {
             let p1 = v[face.v.x - 1].clone();
             let p2 = v[face.v.y - 1].clone();
             let p3 = v[face.v.z - 1].clone();
-            let uv_map = match face.vt {
+            let uv_map = match &face.vt {
                 Some(t) => {
                     let t1 = vt[t.x - 1].clone();
                     let t2 = vt[t.y - 1].clone();
                     let t3 = vt[t.z - 1].clone();
-                    match &face.usemtl {
-                        Some(usemtl) => {
-                            match mtllib.definitions.get(usemtl) {
-                                Some(definition) => {
-                                    match &definition.map {
-                                        Some(map) => Some(UVMap {t1, t2, t3, map: map.clone()}),
-                                        None => None,
-                                    }
-                                },
-                                None => return Err(Box::new(FileError {})),
-                            }
-                        },
-                        None => return Err(Box::new(FileError {})),
-                    }
+                    Some(UVMap {t1, t2, t3})
                 },
                 None => None,
             };
-            let normals = match face.vn {
+            let normals = match &face.vn {
                 Some(n) => {
                     let normals = (
                         -vn[n.x - 1].clone(),
@@ -245,24 +307,48 @@ impl WavefrontObj {
                 },
                 None => None,
             };
-            let mut material = Material::default();
-            if let Some(usemtl) = &face.usemtl {
-                if let Some(definition) = mtllib.definitions.get(usemtl) {
-                    if let Some(diffusion) = &definition.diffusion {
-                        material.colour = diffusion.clone();
+            let material = match face.usemtl.as_ref().and_then(|usemtl| mtllib.definitions.get(usemtl)) {
+                Some(definition) => definition.material(),
+                None => Material::default(),
+            };
+            let face_tangent = uv_map.as_ref().and_then(|uv_map| uv_map.tangent(&(p2.clone() - p1.clone()), &(p3.clone() - p1.clone())));
+            let keys = match (&face.vt, face_tangent) {
+                (Some(t), Some((tangent, handedness))) => {
+                    let n = face.vn.as_ref();
+                    let keys = [
+                        (face.v.x, t.x, n.map(|n| n.x), handedness > 0.0),
+                        (face.v.y, t.y, n.map(|n| n.y), handedness > 0.0),
+                        (face.v.z, t.z, n.map(|n| n.z), handedness > 0.0),
+                    ];
+                    for key in &keys {
+                        let sum = tangents.entry(*key).or_default();
+                        *sum = sum.clone() + tangent.clone();
                     }
-                }
-            }
+                    Some((keys, tangent))
+                },
+                _ => None,
+            };
+            corners.push(keys);
             let triangle = Triangle {
                 p1,
                 p2,
                 p3,
                 normals,
+                tangents: None,
                 material,
                 uv_map,
             };
             obj.triangles.push(triangle);
         }
+        for (triangle, corner) in obj.triangles.iter_mut().zip(corners) {
+            if let Some(([k1, k2, k3], face_tangent)) = corner {
+                let vertex_tangent = |key: &TangentKey| {
+                    let sum = tangents[key].clone();
+                    if sum.magnitude() > MACHEPS { sum.normalise() } else { face_tangent.clone() }
+                };
+                triangle.tangents = Some((vertex_tangent(&k1), vertex_tangent(&k2), vertex_tangent(&k3)));
+            }
+        }
         Ok(obj)
     }
 }
@@ -274,7 +360,7 @@ pub struct WavefrontMtl {
 }
 
 impl WavefrontMtl {
-    pub fn open(path: &str) -> Result<Self, Box<dyn Error>> {
+    pub fn open(path: &str, maps: &mut HashMap<String, Arc<Map>>) -> Result<Self, Box<dyn Error>> {
         let file = BufReader::new(File::open(path)?);
         let mut mtl = WavefrontMtl::default();
         let mut name = None;
@@ -290,28 +376,27 @@ impl WavefrontMtl {
                     }
                     name = Some(expect_string(&mut words)?);
                 },
+                Some("Ka") => definition.ambient = Some(expect_xyz(&mut words)?),
                 Some("Kd") => definition.diffusion = Some(expect_xyz(&mut words)?),
-                Some("map_Kd") => {
-                    let name = expect_string(&mut words)?;
-                    match mtl.maps.get(&name) {
-                        Some(map) => definition.map = Some(map.clone()),
-                        None => {
-                            match Path::new(path).parent() {
-                                Some(parent) => {
-                                    match parent.join(name.clone()).to_str() {
-                                        Some(joined_path_string) => {
-                                            let map = Arc::new(Map::open(joined_path_string)?);
-                                            mtl.maps.insert(name.clone(), map.clone());
-                                            definition.map = Some(map);
-                                        },
-                                        None => return Err(Box::new(FileError {})),
-                                    };
-                                },
-                                None => return Err(Box::new(FileError {})),
-                            }
-                        },
-                    }
+                Some("Ks") => definition.specular = Some(expect_xyz(&mut words)?),
+                Some("Ke") => definition.emission = Some(expect_xyz(&mut words)?),
+                Some("Ns") => definition.shininess = Some(expect_f64(&mut words)?),
+                Some("d") => definition.dissolve = Some(expect_dissolve(&mut words)?),
+                Some("Tr") => definition.dissolve = Some(1.0 - expect_f64(&mut words)?),
+                Some("illum") => match expect_usize_word(&mut words)? {
+                    illumination @ 0..=10 => definition.illumination = Some(illumination),
+                    _ => continue,
                 },
+                Some("Pm") => definition.metallic = Some(expect_f64(&mut words)?),
+                Some("Pr") => definition.roughness = Some(expect_f64(&mut words)?),
+                Some("Ni") => definition.refractive_index = Some(expect_f64(&mut words)?),
+                Some("map_Kd") => definition.map = Some(mtl.expect_texture(&mut words, path, maps)?),
+                Some("map_Ks") => definition.specular_map = Some(mtl.expect_texture(&mut words, path, maps)?),
+                Some("map_Ns") => definition.shininess_map = Some(mtl.expect_texture(&mut words, path, maps)?),
+                Some("map_d") => definition.alpha_map = Some(mtl.expect_texture(&mut words, path, maps)?),
+                Some("map_Bump") | Some("map_bump") | Some("bump") => definition.bump_map = Some(mtl.expect_texture(&mut words, path, maps)?),
+                Some("norm") | Some("map_Norm") => definition.normal_map = Some(mtl.expect_texture(&mut words, path, maps)?),
+                Some("map_Ke") => definition.emission_map = Some(mtl.expect_texture(&mut words, path, maps)?),
                 _ => continue,
             }
         }
@@ -322,6 +407,109 @@ impl WavefrontMtl {
     }
 }
 
+impl WavefrontMtl {
+    fn expect_texture(&mut self, words: &mut SplitWhitespace, path: &str, maps: &mut HashMap<String, Arc<Map>>) -> Result<Texture, Box<dyn Error>> {
+        let words: Vec<&str> = words.collect();
+        let mut offset = (0.0, 0.0);
+        let mut scale = (1.0, 1.0);
+        let mut bump_multiplier = 1.0;
+        let mut wrap = Wrap::Repeat;
+        let mut index = 0;
+        while index < words.len() && words[index].starts_with('-') {
+            let option = words[index];
+            index += 1;
+            match option {
+                "-o" => {
+                    let values = option_values(&words, &mut index)?;
+                    offset = (values[0], values.get(1).copied().unwrap_or(0.0));
+                },
+                "-s" => {
+                    let values = option_values(&words, &mut index)?;
+                    scale = (values[0], values.get(1).copied().unwrap_or(1.0));
+                },
+                "-t" => {
+                    option_values(&words, &mut index)?;
+                },
+                "-bm" => {
+                    bump_multiplier = option_word(&words, &mut index)?.parse::<f64>().map_err(|_| FileError {})?;
+                },
+                "-clamp" => {
+                    wrap = match option_word(&words, &mut index)? {
+                        "on" => Wrap::ClampToEdge,
+                        _ => Wrap::Repeat,
+                    };
+                },
+                "-mm" => {
+                    option_word(&words, &mut index)?;
+                    option_word(&words, &mut index)?;
+                },
+                _ => {
+                    option_word(&words, &mut index)?;
+                },
+            }
+        }
+        if index >= words.len() {
+            return Err(Box::new(FileError {}));
+        }
+        let name = words[index..].join(" ");
+        let mut texture = Texture::new(self.load_map(&name, path, maps)?);
+        texture.offset = offset;
+        texture.scale = scale;
+        texture.bump_multiplier = bump_multiplier;
+        texture.wrap = wrap;
+        Ok(texture)
+    }
+
+    fn load_map(&mut self, name: &str, path: &str, maps: &mut HashMap<String, Arc<Map>>) -> Result<Arc<Map>, Box<dyn Error>> {
+        if let Some(map) = self.maps.get(name) {
+            return Ok(map.clone());
+        }
+        let joined_path = match Path::new(path).parent() {
+            Some(parent) => parent.join(name),
+            None => return Err(Box::new(FileError {})),
+        };
+        let joined_path_string = match joined_path.to_str() {
+            Some(joined_path_string) => joined_path_string,
+            None => return Err(Box::new(FileError {})),
+        };
+        let map = match maps.get(joined_path_string) {
+            Some(map) => map.clone(),
+            None => {
+                let map = Arc::new(Map::open(joined_path_string)?);
+                maps.insert(joined_path_string.to_string(), map.clone());
+                map
+            },
+        };
+        self.maps.insert(name.to_string(), map.clone());
+        Ok(map)
+    }
+}
+
+fn option_word<'a>(words: &[&'a str], index: &mut usize) -> Result<&'a str, FileError> {
+    match words.get(*index) {
+        Some(word) => {
+            *index += 1;
+            Ok(word)
+        },
+        None => Err(FileError {}),
+    }
+}
+
+fn option_values(words: &[&str], index: &mut usize) -> Result<Vec<f64>, FileError> {
+    let mut values = vec![];
+    while values.len() < 3 && *index < words.len() - 1 {
+        match words[*index].parse::<f64>() {
+            Ok(value) => values.push(value),
+            Err(_) => break,
+        }
+        *index += 1;
+    }
+    if values.is_empty() {
+        return Err(FileError {});
+    }
+    Ok(values)
+}
+
 impl From<WavefrontObj> for Mesh {
     fn from(obj: WavefrontObj) -> Self {
         Mesh {
@@ -333,3 +521,88 @@ impl From<WavefrontObj> for Mesh {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(path: &str) -> Result<WavefrontMtl, Box<dyn Error>> {
+        WavefrontMtl::open(path, &mut HashMap::new())
+    }
+
+    #[test]
+    fn parses_lighting_statements() {
+        let mtl = open("objs/mtl/sample.mtl").expect("mtl");
+        let glass = mtl.definitions["glass"].material();
+        assert_eq!(glass.ambient, 0.5);
+        assert_eq!(glass.colour, Vert::new(0.5, 0.5, 0.5));
+        assert_eq!(glass.specular, 1.0);
+        assert_eq!(glass.shininess, 64.0);
+        assert_eq!(glass.transparency, 0.75);
+        assert_eq!(glass.refractive_index, 1.5);
+        assert_eq!(glass.illumination, 4);
+        assert_eq!(glass.emission, Vert::default());
+    }
+
+    #[test]
+    fn inverts_tr_to_transparency() {
+        let mtl = open("objs/mtl/sample.mtl").expect("mtl");
+        let lamp = mtl.definitions["lamp"].material();
+        assert_eq!(lamp.transparency, 0.75);
+        assert_eq!(lamp.emission, Vert::new(0.5, 0.25, 0.0));
+        assert_eq!(lamp.illumination, 1);
+    }
+
+    #[test]
+    fn keeps_defaults_for_missing_statements() {
+        let mtl = open("objs/mtl/sample.mtl").expect("mtl");
+        let lamp = mtl.definitions["lamp"].material();
+        let default = Material::default();
+        assert_eq!(lamp.ambient, default.ambient);
+        assert_eq!(lamp.colour, default.colour);
+        assert_eq!(lamp.refractive_index, default.refractive_index);
+    }
+
+    #[test]
+    fn parses_gitaroo_materials() {
+        let mtl = open("objs/gitaroo/u1.mtl").expect("mtl");
+        assert_eq!(mtl.definitions.len(), 2);
+        let body = mtl.definitions["u1:U1Tbody_re01_mesh_0SG"].material();
+        assert_eq!(body.ambient, 0.0);
+        assert_eq!(body.shininess, 18.0);
+        assert!(body.map.is_some());
+    }
+
+    #[test]
+    fn reads_halo_dissolve() {
+        let mtl = open("objs/mtl/sample.mtl").expect("mtl");
+        assert_eq!(mtl.definitions["halo"].material().transparency, 0.5);
+    }
+
+    #[test]
+    fn rejects_bad_illumination() {
+        match open("objs/mtl/bad_illum.mtl") {
+            Ok(_) => panic!("bad_illum.mtl should not parse"),
+            Err(error) => assert!(error.is::<FileError>()),
+        }
+    }
+
+    #[test]
+    fn ignores_unknown_illumination() {
+        let mtl = open("objs/mtl/unknown_illum.mtl").expect("mtl");
+        assert_eq!(mtl.definitions["broken"].material().illumination, Material::default().illumination);
+    }
+
+    #[test]
+    fn shares_tangents_between_faces() {
+        let mesh = Mesh::load_obj("objs/bent.obj").expect("obj");
+        let (a1, a2, a3) = mesh.triangles[0].tangents.clone().expect("tangents");
+        let (b1, b2, b3) = mesh.triangles[1].tangents.clone().expect("tangents");
+        let diagonal = Vert::new(1.0, 0.0, 1.0).normalise();
+        assert!((a1 - Vert::new(1.0, 0.0, 0.0)).magnitude() < 1e-9);
+        assert!((b2 - diagonal.clone()).magnitude() < 1e-9);
+        assert_eq!(a2, b1);
+        assert_eq!(a3, b3);
+        assert!((a2 - (Vert::new(1.0, 0.0, 0.0) + diagonal).normalise()).magnitude() < 1e-9);
+    }
+}
@@ -36,6 +36,8 @@ impl CameraCompute {
         let pixel = self.transform.multiply_with_vert(&Vert::new(world_x, world_y, -1.0));
         let origin = self.transform.multiply_with_vert(&Vert::new(0.0, 0.0, 0.0));
         let direction = (pixel - origin.clone()).normalise();
-        Ray::new(origin, direction)
+        let mut ray = Ray::new(origin, direction);
+        ray.spread = self.pixel_size;
+        ray
     }
 }
@@ -0,0 +1,35 @@
+use crate::{BoundingBox, Bvh, Intersection, Ray, Shape};
+use std::sync::Arc;
+
+pub struct ComputedWorld {
+    pub objects: Vec<Arc<dyn Shape>>,
+    pub unbounded: Vec<Arc<dyn Shape>>,
+    pub bvh: Bvh,
+}
+
+impl ComputedWorld {
+    pub fn new(objects: Vec<Arc<dyn Shape>>) -> ComputedWorld {
+        let (objects, unbounded): (Vec<_>, Vec<_>) = objects.into_iter().partition(|object| object.bounds().is_finite());
+        let bounds: Vec<BoundingBox> = objects.iter().map(|object| object.bounds()).collect();
+        let bvh = Bvh::new(&bounds);
+        ComputedWorld {objects, unbounded, bvh}
+    }
+
+    pub fn closest_hit(&self, ray: &Ray, max_time: f64) -> Option<Intersection<'_>> {
+        let mut closest = self.bvh.closest_hit(ray, max_time, |index, max_time| {
+            self.objects[index].closest_hit(ray, max_time).map(|intersection| (intersection.time, intersection))
+        });
+        for object in &self.unbounded {
+            let max_time = closest.as_ref().map_or(max_time, |intersection: &Intersection| intersection.time);
+            if let Some(intersection) = object.closest_hit(ray, max_time) {
+                closest = Some(intersection);
+            }
+        }
+        closest
+    }
+
+    pub fn any_hit(&self, ray: &Ray, max_time: f64) -> bool {
+        self.bvh.any_hit(ray, max_time, |index, max_time| self.objects[index].any_hit(ray, max_time))
+            || self.unbounded.iter().any(|object| object.any_hit(ray, max_time))
+    }
+}
@@ -1,30 +1,61 @@
-use crate::{ComputedTriangle, Intersection, Ray, Vert, MACHEPS};
+use crate::{Intersection, Material, Ray, Vert, MACHEPS};
 
 pub struct IntersectionCompute<'a> {
     pub time: f64,
-    pub triangle: &'a ComputedTriangle,
+    pub material: &'a Material,
     pub point: Vert,
     pub norm_v: Vert,
     pub colour: Vert,
+    pub uv: Option<(f64, f64)>,
+    pub footprint: f64,
+    pub spread: f64,
     pub over_point: Vert,
+    pub under_point: Vert,
     pub eye_v: Vert,
+    pub reflect_v: Vert,
+    pub inside: bool,
 }
 
 impl<'a> IntersectionCompute<'a> {
-    pub fn new(ray: &Ray, intersection: &'a Intersection) -> IntersectionCompute<'a> {
+    pub fn new(ray: &Ray, intersection: &Intersection<'a>) -> IntersectionCompute<'a> {
         let point = ray.pos(intersection.time);
-        let norm_v = intersection.triangle.norm_vec_at_uv(intersection.u, intersection.v);
-        let colour = intersection.triangle.colour_at_uv(intersection.u, intersection.v);
+        let surface = intersection.surface;
+        let (u, v) = (intersection.u, intersection.v);
+        let (norm_v, local_point) = match intersection.instance {
+            Some(instance) => {
+                let local_point = instance.transform.point_to_object(&point);
+                let norm_v = surface.norm_vec_at(&local_point, u, v);
+                (instance.transform.normal_to_world(&norm_v), local_point)
+            },
+            None => (surface.norm_vec_at(&point, u, v), point.clone()),
+        };
+        let uv = surface.uv_at(&local_point, u, v);
+        let norm_v = if intersection.inverted { -norm_v } else { norm_v };
+        let inside = ray.direction.dot_product(&norm_v) < 0.0;
+        let norm_v = if inside { -norm_v } else { norm_v };
+        let material = surface.material();
+        let footprint = ray.footprint(intersection.time);
+        let cos = ray.direction.dot_product(&norm_v).abs() / (ray.direction.magnitude() * norm_v.magnitude());
+        let uv_density = surface.uv_density() * intersection.instance.map_or(1.0, |instance| instance.transform.object_scale());
+        let colour = material.colour_at(&surface.object_point(&local_point), uv, footprint * uv_density / cos.max(0.05));
         let over_point = point.clone() - norm_v.multiply_by_scalar(MACHEPS);
+        let under_point = point.clone() + norm_v.multiply_by_scalar(MACHEPS);
         let eye_v = -ray.direction.clone();
+        let reflect_v = ray.direction.clone() - norm_v.multiply_by_scalar(2.0 * ray.direction.dot_product(&norm_v));
         IntersectionCompute {
             time: intersection.time,
-            triangle: intersection.triangle,
+            material,
             point,
             norm_v,
             colour,
+            uv,
+            footprint,
+            spread: ray.spread,
             over_point,
+            under_point,
             eye_v,
+            reflect_v,
+            inside,
         }
     }
 }
@@ -1,16 +1,20 @@
 mod camera;
 mod camera_compute;
 mod camera_iterator;
+mod computed_world;
 mod light;
 mod ray;
+mod shading;
 mod world;
 mod intersection_compute;
 mod world_iterator;
 pub use camera::Camera;
 pub use camera_compute::CameraCompute;
 pub use camera_iterator::CameraIterator;
+pub use computed_world::ComputedWorld;
 pub use light::Light;
-pub use ray::{Intersection, SortByTime, Ray};
+pub use ray::{Intersection, Ray, SortByTime};
+pub use shading::{BlinnPhong, Lambert, MetallicRoughness, OrenNayar, Phong, ShadingModel};
 pub use world::World;
 use world::colour_at_ray;
 pub use intersection_compute::IntersectionCompute;
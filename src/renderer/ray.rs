@@ -1,23 +1,31 @@
-use crate::{ComputedTriangle, Matrix, Vert};
+use crate::{Instance, Matrix, Surface, Vert};
 
 pub struct Ray {
     pub origin: Vert,
     pub direction: Vert,
+    pub width: f64,
+    pub spread: f64,
 }
 
 impl Ray {
     pub fn new(origin: Vert, direction: Vert) -> Ray {
-        Ray {origin, direction}
+        Ray {origin, direction, width: 0.0, spread: 0.0}
+    }
+
+    pub fn footprint(&self, time: f64) -> f64 {
+        self.width + self.spread * time
     }
 
     pub fn pos(&self, time: f64) -> Vert {
         self.origin.clone() + self.direction.multiply_by_scalar(time)
     }
 
-    pub fn transform(&self, matrix: Matrix) -> Ray {
+    pub fn transform(&self, matrix: &Matrix) -> Ray {
         Ray {
             origin: matrix.multiply_with_vert(&self.origin),
-            direction: matrix.multiply_with_vert(&self.direction),
+            direction: matrix.multiply_with_direction(&self.direction),
+            width: self.width,
+            spread: self.spread,
         }
     }
 }
@@ -36,16 +44,20 @@ pub struct Intersection<'a> {
     pub time: f64,
     pub u: f64,
     pub v: f64,
-    pub triangle: &'a ComputedTriangle,
+    pub surface: &'a dyn Surface,
+    pub instance: Option<&'a Instance>,
+    pub inverted: bool,
 }
 
 impl<'a> Intersection<'a> {
-    pub fn new(time: f64, u: f64, v: f64, triangle: &'a ComputedTriangle) -> Intersection<'a> {
+    pub fn new(time: f64, u: f64, v: f64, surface: &'a dyn Surface) -> Intersection<'a> {
         Intersection {
             time,
             u,
             v,
-            triangle,
+            surface,
+            instance: None,
+            inverted: false,
         }
     }
 }
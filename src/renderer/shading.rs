@@ -0,0 +1,114 @@
+use crate::{IntersectionCompute, Light, Vert, PI};
+
+pub trait ShadingModel: Send + Sync {
+    fn shade(&self, light: &Light, compute: &IntersectionCompute, light_v: &Vert) -> Vert;
+}
+
+pub struct Phong;
+
+pub struct BlinnPhong;
+
+pub struct Lambert;
+
+pub struct OrenNayar {
+    pub roughness: f64,
+}
+
+pub struct MetallicRoughness {
+    pub metallic: f64,
+    pub roughness: f64,
+}
+
+fn lambert(light: &Light, compute: &IntersectionCompute, light_v: &Vert) -> Vert {
+    let effective_colour = light.intensity.clone() * compute.colour.clone();
+    effective_colour.multiply_by_scalar(compute.material.diffuse * light_v.dot_product(&compute.norm_v))
+}
+
+impl ShadingModel for Phong {
+    fn shade(&self, light: &Light, compute: &IntersectionCompute, light_v: &Vert) -> Vert {
+        let diffuse = lambert(light, compute, light_v);
+        let reflect_v = light_v.clone() - compute.norm_v.multiply_by_scalar(2.0).multiply_by_scalar(light_v.dot_product(&compute.norm_v));
+        let reflect_dot_eye = reflect_v.dot_product(&compute.eye_v);
+        if reflect_dot_eye <= 0.0 || compute.material.illumination == 1 {
+            diffuse
+        } else {
+            let factor = reflect_dot_eye.powf(compute.material.shininess_at(compute.uv));
+            let specular = light.intensity.multiply_by_scalar(compute.material.specular_at(compute.uv) * factor);
+            diffuse + specular
+        }
+    }
+}
+
+impl ShadingModel for BlinnPhong {
+    fn shade(&self, light: &Light, compute: &IntersectionCompute, light_v: &Vert) -> Vert {
+        let diffuse = lambert(light, compute, light_v);
+        let half_v = (compute.eye_v.clone() - light_v.clone()).normalise();
+        let normal_dot_half = -compute.norm_v.dot_product(&half_v);
+        if normal_dot_half <= 0.0 || compute.material.illumination == 1 {
+            diffuse
+        } else {
+            let factor = normal_dot_half.powf(compute.material.shininess_at(compute.uv));
+            let specular = light.intensity.multiply_by_scalar(compute.material.specular_at(compute.uv) * factor);
+            diffuse + specular
+        }
+    }
+}
+
+impl ShadingModel for Lambert {
+    fn shade(&self, light: &Light, compute: &IntersectionCompute, light_v: &Vert) -> Vert {
+        lambert(light, compute, light_v)
+    }
+}
+
+impl ShadingModel for OrenNayar {
+    fn shade(&self, light: &Light, compute: &IntersectionCompute, light_v: &Vert) -> Vert {
+        let normal = -compute.norm_v.clone();
+        let to_light = -light_v.clone();
+        let sigma_squared = self.roughness.powi(2);
+        let a = 1.0 - 0.5 * sigma_squared / (sigma_squared + 0.33);
+        let b = 0.45 * sigma_squared / (sigma_squared + 0.09);
+        let normal_dot_light = normal.dot_product(&to_light).clamp(0.0, 1.0);
+        let normal_dot_eye = normal.dot_product(&compute.eye_v).clamp(0.0, 1.0);
+        let theta_i = normal_dot_light.acos();
+        let theta_r = normal_dot_eye.acos();
+        let light_tangent = to_light.clone() - normal.multiply_by_scalar(normal_dot_light);
+        let eye_tangent = compute.eye_v.clone() - normal.multiply_by_scalar(normal_dot_eye);
+        let lengths = light_tangent.magnitude() * eye_tangent.magnitude();
+        let cos_phi = if lengths > 0.0 { (light_tangent.dot_product(&eye_tangent) / lengths).max(0.0) } else { 0.0 };
+        let (alpha, beta) = (theta_i.max(theta_r), theta_i.min(theta_r));
+        let factor = a + b * cos_phi * alpha.sin() * beta.tan();
+        lambert(light, compute, light_v).multiply_by_scalar(factor)
+    }
+}
+
+impl ShadingModel for MetallicRoughness {
+    fn shade(&self, light: &Light, compute: &IntersectionCompute, light_v: &Vert) -> Vert {
+        let light_dot_normal = light_v.dot_product(&compute.norm_v);
+        let normal = -compute.norm_v.clone();
+        let to_light = -light_v.clone();
+        let half_v = (to_light + compute.eye_v.clone()).normalise();
+        let normal_dot_eye = normal.dot_product(&compute.eye_v).max(1e-4);
+        let normal_dot_half = normal.dot_product(&half_v).max(0.0);
+        let eye_dot_half = compute.eye_v.dot_product(&half_v).max(0.0);
+        let metallic = self.metallic.clamp(0.0, 1.0);
+        let roughness = self.roughness.clamp(0.04, 1.0);
+        let base = compute.colour.clone();
+        let f0 = Vert::new(0.04, 0.04, 0.04).multiply_by_scalar(1.0 - metallic) + base.multiply_by_scalar(metallic);
+        let fresnel = f0.clone() + (Vert::new(1.0, 1.0, 1.0) - f0).multiply_by_scalar((1.0 - eye_dot_half).powi(5));
+        let alpha_squared = roughness.powi(4);
+        let distribution = alpha_squared / (PI * (normal_dot_half.powi(2) * (alpha_squared - 1.0) + 1.0).powi(2));
+        let k = (roughness + 1.0).powi(2) / 8.0;
+        let geometry = smith(light_dot_normal, k) * smith(normal_dot_eye, k);
+        let specular = if compute.material.illumination == 1 {
+            Vert::default()
+        } else {
+            fresnel.multiply_by_scalar(distribution * geometry / (4.0 * light_dot_normal.max(1e-4) * normal_dot_eye))
+        };
+        let diffuse = (Vert::new(1.0, 1.0, 1.0) - fresnel) * base.multiply_by_scalar((1.0 - metallic) / PI);
+        (diffuse + specular).multiply_by_scalar(PI * light_dot_normal) * light.intensity.clone()
+    }
+}
+
+fn smith(cos: f64, k: f64) -> f64 {
+    cos / (cos * (1.0 - k) + k)
+}
@@ -1,10 +1,11 @@
 use crate::{
-    Camera, CameraIterator, ComputeMeshes, ComputedMesh, Light, Mesh, Ray, SortByTime, Vert, IntersectionCompute,
-    WorldIterator,
+    Camera, CameraIterator, ComputedMesh, ComputedWorld, Instance, Light, Map, Mesh, Ray, Shape, Vert,
+    IntersectionCompute, WorldIterator,
 };
 use image::{ImageBuffer, Rgba};
 use std::cmp::{max, min};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
 use std::thread::{spawn, JoinHandle};
 
 #[derive(Clone)]
@@ -12,43 +13,148 @@ pub struct World {
     pub camera: Camera,
     pub lights: Vec<Arc<Light>>,
     pub meshes: Vec<Arc<Mesh>>,
+    pub instances: Vec<Arc<Instance>>,
+    pub shapes: Vec<Arc<dyn Shape>>,
+    pub maps: HashMap<String, Arc<Map>>,
+    pub max_depth: usize,
+    cache: Vec<CachedMesh>,
 }
 
-pub fn colour_at_ray(ray: &Ray, lights: &Vec<Arc<Light>>, computed_meshes: &Vec<Arc<ComputedMesh>>) -> Vert {
-    let mut intersections = vec![];
-    for mesh in computed_meshes.into_iter() {
-        mesh.intersects_with(&ray, &mut intersections);
+#[derive(Clone)]
+struct CachedMesh {
+    mesh: Weak<Mesh>,
+    position: Vert,
+    rotation: Vert,
+    scale: Vert,
+    computed_mesh: Arc<ComputedMesh>,
+}
+
+impl CachedMesh {
+    fn new(mesh: &Arc<Mesh>, computed_mesh: Arc<ComputedMesh>) -> CachedMesh {
+        CachedMesh {
+            mesh: Arc::downgrade(mesh),
+            position: mesh.position.clone(),
+            rotation: mesh.rotation.clone(),
+            scale: mesh.scale.clone(),
+            computed_mesh,
+        }
     }
-    intersections.sort_by_time();
+
+    fn computed_from(&self, mesh: &Arc<Mesh>) -> bool {
+        self.mesh.as_ptr() == Arc::as_ptr(mesh)
+    }
+
+    fn same_transform(&self, mesh: &Mesh) -> bool {
+        self.position == mesh.position && self.rotation == mesh.rotation && self.scale == mesh.scale
+    }
+}
+
+pub fn colour_at_ray(ray: &Ray, lights: &[Arc<Light>], computed_world: &ComputedWorld, remaining: usize, media: &[f64]) -> Vert {
     let mut col: Option<Vert> = None;
-    for light in lights.into_iter() {
-        if let Some(first_intersection) = intersections.first() {
-            let compute = IntersectionCompute::new(&ray, first_intersection);
-            let shadowed = if light.casts_shadows {
-                light.shadowed(compute.over_point.clone(), &computed_meshes)
-            } else {
-                false
-            };
-            let light_rgb = light.lighting(&compute, shadowed);
-            col = match col {
-                Some(prev_light_rgb) => Some(prev_light_rgb * light_rgb),
-                None => Some(light_rgb),
-            }
-        }
+    if let Some(intersection) = computed_world.closest_hit(ray, f64::INFINITY) {
+        let compute = IntersectionCompute::new(ray, &intersection);
+        let surface = surface_colour(&compute, lights, computed_world);
+        let emission = compute.material.emission_at(compute.uv);
+        let transparency = compute.material.transparency_at(compute.uv);
+        col = if transparency > 0.0 {
+            let indices = refractive_indices(&compute, media);
+            let reflectance = schlick(&compute, indices);
+            let reflection = compute.material.reflective * (1.0 - transparency) + reflectance * transparency;
+            let reflected = reflected_colour(&compute, lights, computed_world, remaining, media, reflection);
+            let refracted = refracted_colour(&compute, lights, computed_world, remaining, indices, media, transparency * (1.0 - reflectance));
+            Some(surface.multiply_by_scalar(1.0 - transparency) + emission + reflected + refracted)
+        } else {
+            let reflected = reflected_colour(&compute, lights, computed_world, remaining, media, compute.material.reflective);
+            Some(surface + emission + reflected)
+        };
+    }
+    col.unwrap_or_default()
+}
+
+fn surface_colour(compute: &IntersectionCompute, lights: &[Arc<Light>], computed_world: &ComputedWorld) -> Vert {
+    if compute.material.illumination == 0 {
+        return compute.colour.clone();
     }
-    match col {
-        Some(col) => col,
-        None => Vert::default(),
+    if lights.is_empty() {
+        return Vert::default();
     }
+    let ambient_intensity = lights.iter().fold(Vert::default(), |sum, light| sum + light.intensity.clone()).multiply_by_scalar(1.0 / lights.len() as f64);
+    let mut col = (ambient_intensity * compute.colour.clone()).multiply_by_scalar(compute.material.ambient);
+    for light in lights.iter() {
+        let shadowed = if light.casts_shadows {
+            light.shadowed(compute.over_point.clone(), computed_world)
+        } else {
+            false
+        };
+        col = col + light.lighting(compute, shadowed);
+    }
+    col
+}
+
+fn refractive_indices(compute: &IntersectionCompute, media: &[f64]) -> (f64, f64) {
+    let n1 = media.last().copied().unwrap_or(1.0);
+    if compute.inside {
+        (n1, media.iter().rev().nth(1).copied().unwrap_or(1.0))
+    } else {
+        (n1, compute.material.refractive_index)
+    }
+}
+
+fn reflected_colour(compute: &IntersectionCompute, lights: &[Arc<Light>], computed_world: &ComputedWorld, remaining: usize, media: &[f64], weight: f64) -> Vert {
+    if remaining == 0 || weight <= 0.0 {
+        return Vert::default();
+    }
+    let mut ray = Ray::new(compute.over_point.clone(), compute.reflect_v.clone());
+    ray.width = compute.footprint;
+    ray.spread = compute.spread;
+    colour_at_ray(&ray, lights, computed_world, remaining - 1, media).multiply_by_scalar(weight)
 }
 
-fn render_thread(computed_meshes: Vec<Arc<ComputedMesh>>, lights: Vec<Arc<Light>>, camera_iter: CameraIterator) -> JoinHandle<Vec<u8>> {
+fn refracted_colour(compute: &IntersectionCompute, lights: &[Arc<Light>], computed_world: &ComputedWorld, remaining: usize, (n1, n2): (f64, f64), media: &[f64], weight: f64) -> Vert {
+    if remaining == 0 || weight <= 0.0 {
+        return Vert::default();
+    }
+    let n_ratio = n1 / n2;
+    let cos_i = -compute.eye_v.dot_product(&compute.norm_v);
+    let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+    if sin2_t > 1.0 {
+        return Vert::default();
+    }
+    let mut refracted_media = media.to_vec();
+    if compute.inside {
+        refracted_media.pop();
+    } else {
+        refracted_media.push(compute.material.refractive_index);
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let direction = compute.norm_v.multiply_by_scalar(cos_t - n_ratio * cos_i) - compute.eye_v.multiply_by_scalar(n_ratio);
+    let mut ray = Ray::new(compute.under_point.clone(), direction.normalise());
+    ray.width = compute.footprint;
+    ray.spread = compute.spread;
+    colour_at_ray(&ray, lights, computed_world, remaining - 1, &refracted_media).multiply_by_scalar(weight)
+}
+
+fn schlick(compute: &IntersectionCompute, (n1, n2): (f64, f64)) -> f64 {
+    let mut cos = -compute.eye_v.dot_product(&compute.norm_v);
+    if n1 > n2 {
+        let sin2_t = (n1 / n2).powi(2) * (1.0 - cos.powi(2));
+        if sin2_t > 1.0 {
+            return 1.0;
+        }
+        cos = (1.0 - sin2_t).sqrt();
+    }
+    let r0 = ((n1 - n2) / (n1 + n2)).powi(2);
+    r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+}
+
+fn render_thread(computed_world: Arc<ComputedWorld>, lights: Vec<Arc<Light>>, camera_iter: CameraIterator, max_depth: usize) -> JoinHandle<Vec<u8>> {
     spawn(move || {
         let mut pixels = vec![];
         let iter = WorldIterator {
-            computed_meshes,
+            computed_world,
             lights,
             camera_iter,
+            max_depth,
         };
         for rgb in iter {
             let rgba: [u8; 4] = rgb.into();
@@ -65,6 +171,11 @@ impl World {
             camera: Camera::default(),
             lights: vec![],
             meshes: vec![],
+            instances: vec![],
+            shapes: vec![],
+            maps: HashMap::new(),
+            max_depth: 5,
+            cache: vec![],
         }
     }
 
@@ -76,17 +187,69 @@ impl World {
         self.meshes.push(Arc::new(mesh));
     }
 
-    pub fn render_to_png(&self, path: &str, number_of_threads: usize) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn load_mesh(&mut self, path: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        let mesh = Mesh::load_obj_with_maps(path, &mut self.maps)?;
+        self.add_mesh(mesh);
+        Ok(self.meshes.len() - 1)
+    }
+
+    pub fn mesh_mut(&mut self, index: usize) -> &mut Mesh {
+        Arc::make_mut(&mut self.meshes[index])
+    }
+
+    pub fn set_mesh_transform(&mut self, index: usize, position: Vert, rotation: Vert, scale: Vert) {
+        let cached = self.cache.get(index).is_some_and(|cached| cached.computed_from(&self.meshes[index]));
+        let mesh = Arc::make_mut(&mut self.meshes[index]);
+        mesh.position = position;
+        mesh.rotation = rotation;
+        mesh.scale = scale;
+        if cached {
+            self.cache[index].mesh = Arc::downgrade(&self.meshes[index]);
+        }
+    }
+
+    pub fn add_instance(&mut self, instance: Instance) {
+        self.instances.push(Arc::new(instance));
+    }
+
+    pub fn add_shape<S: Shape + 'static>(&mut self, shape: S) {
+        self.shapes.push(Arc::new(shape));
+    }
+
+    fn compute_meshes(&mut self) -> Vec<Arc<ComputedMesh>> {
+        let mut cache = vec![];
+        for (index, mesh) in self.meshes.iter().enumerate() {
+            let computed_mesh = match self.cache.get(index) {
+                Some(cached) if cached.computed_from(mesh) => {
+                    if cached.same_transform(mesh) {
+                        cached.computed_mesh.clone()
+                    } else {
+                        Arc::new(mesh.refit(&cached.computed_mesh))
+                    }
+                },
+                _ => Arc::new(mesh.compute()),
+            };
+            cache.push(CachedMesh::new(mesh, computed_mesh));
+        }
+        self.cache = cache;
+        self.cache.iter().map(|cached| cached.computed_mesh.clone()).collect()
+    }
+
+    pub fn render_to_png(&mut self, path: &str, number_of_threads: usize) -> Result<(), Box<dyn std::error::Error>> {
         let mut pixels = vec![];
-        let computed_meshes = self.meshes.compute_meshes();
+        let mut objects: Vec<Arc<dyn Shape>> = vec![];
+        objects.extend(self.compute_meshes().into_iter().map(|mesh| mesh as Arc<dyn Shape>));
+        objects.extend(self.instances.iter().map(|instance| instance.clone() as Arc<dyn Shape>));
+        objects.extend(self.shapes.iter().cloned());
+        let computed_world = Arc::new(ComputedWorld::new(objects));
         let mut threads = vec![];
         let step = max((self.camera.height as f64 / number_of_threads as f64) as usize, 1);
         for start_y in (0..self.camera.height).step_by(step) {
-            let computed_meshes = computed_meshes.clone();
+            let computed_world = computed_world.clone();
             let lights = self.lights.clone();
             let end_y = min(start_y + step, self.camera.height);
             let camera_iter = self.camera.part_iter(start_y, end_y);
-            let thread = render_thread(computed_meshes, lights, camera_iter);
+            let thread = render_thread(computed_world, lights, camera_iter, self.max_depth);
             threads.push(thread);
         }
         for thread in threads {
@@ -103,3 +266,52 @@ impl World {
         Ok(())
     }
 }
+
+impl Default for World {
+    fn default() -> World {
+        World::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Material, Sphere};
+
+    fn centre_colour(number_of_lights: usize) -> Vert {
+        let shape: Arc<dyn Shape> = Arc::new(Sphere::new(Vert::default(), 1.0));
+        let computed_world = ComputedWorld::new(vec![shape]);
+        let lights: Vec<Arc<Light>> = (0..number_of_lights).map(|_| {
+            let mut light = Light::new();
+            light.position = Vert::new(0.0, 0.0, -5.0);
+            light.intensity = Vert::new(0.2, 0.2, 0.2);
+            Arc::new(light)
+        }).collect();
+        let ray = Ray::new(Vert::new(0.0, 0.0, -5.0), Vert::new(0.0, 0.0, 1.0));
+        colour_at_ray(&ray, &lights, &computed_world, 5, &[])
+    }
+
+    fn assert_lit_by(number_of_lights: usize) {
+        let material = Material::default();
+        let expected = material.colour.multiply_by_scalar(0.2 * (material.ambient + number_of_lights as f64));
+        let colour = centre_colour(number_of_lights);
+        for (actual, expected) in [(colour.x, expected.x), (colour.y, expected.y), (colour.z, expected.z)] {
+            assert!((actual - expected).abs() < 1e-9, "{} lights: expected {}, got {}", number_of_lights, expected, actual);
+        }
+    }
+
+    #[test]
+    fn one_light() {
+        assert_lit_by(1);
+    }
+
+    #[test]
+    fn two_lights_add_direct_light_and_share_ambient() {
+        assert_lit_by(2);
+    }
+
+    #[test]
+    fn ten_lights_add_direct_light_and_share_ambient() {
+        assert_lit_by(10);
+    }
+}
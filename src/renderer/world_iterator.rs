@@ -1,11 +1,12 @@
-use crate::{CameraIterator, ComputedMesh, Light, Vert};
+use crate::{CameraIterator, ComputedWorld, Light, Vert};
 use super::colour_at_ray;
 use std::sync::Arc;
 
 pub struct WorldIterator {
-    pub computed_meshes: Vec<Arc<ComputedMesh>>,
+    pub computed_world: Arc<ComputedWorld>,
     pub lights: Vec<Arc<Light>>,
     pub camera_iter: CameraIterator,
+    pub max_depth: usize,
 }
 
 impl Iterator for WorldIterator {
@@ -14,7 +15,7 @@ impl Iterator for WorldIterator {
     fn next(&mut self) -> Option<Vert> {
         match self.camera_iter.next() {
             Some(ray) => {
-                let col = colour_at_ray(&ray, &self.lights, &self.computed_meshes);
+                let col = colour_at_ray(&ray, &self.lights, &self.computed_world, self.max_depth, &[]);
                 Some(col)
             },
             None => None,